@@ -1,5 +1,17 @@
-use crate::common::*;
-use clap::{Parser, Subcommand};
+use crate::completions::{
+    CONFIG_FILE_ENV_VAR, CompletionShell, DATA_FILE_ENV_VAR, task_candidates, timer_name_candidates,
+};
+use crate::export::ExportFormat;
+use crate::import::{ColumnMapping, ImportFormat};
+use crate::report::{ColorMode, ReportBy, ReportFormat, ReportPeriod, ReportSort};
+use chrono::{DateTime, Utc};
+use clap::{ArgGroup, Parser, Subcommand};
+use clap_complete::ArgValueCandidates;
+use std::path::PathBuf;
+use tomo::common::*;
+use tomo::history::TimeBound;
+use tomo::render::OutputFormat;
+use tomo::time::NOW_ENV_VAR;
 
 /// tomo is a no-frills pomodoro progress indicator intended for tmux and similar terminal multiplexers
 #[derive(Parser, Debug)]
@@ -39,9 +51,65 @@ pub struct Args {
     #[arg(long = "break-msg", value_name = "STRING")]
     #[clap(default_value = DEFAULT_BREAK_MSG)]
     pub break_msg: String,
+    /// Message to show when no timer is running (defaults to the config file's idle_msg); "{idle}" is replaced with how long it's been stopped
+    #[arg(long = "idle-msg", value_name = "STRING")]
+    pub idle_msg: Option<String>,
     /// tomo's data file (defaults to <YOUR_DATA_DIR>/tomo/.tomo)
-    #[arg(long = "data-file", value_name = "STRING", global = true)]
+    #[arg(
+        long = "data-file",
+        value_name = "STRING",
+        global = true,
+        env = DATA_FILE_ENV_VAR
+    )]
     pub data_file: Option<String>,
+    /// tomo's config file (defaults to <YOUR_CONFIG_DIR>/tomo/config.toml)
+    #[arg(
+        long = "config-file",
+        value_name = "STRING",
+        global = true,
+        env = CONFIG_FILE_ENV_VAR
+    )]
+    pub config_file: Option<String>,
+    /// Named timer to act on; can be repeated to show several timers in one segment
+    #[arg(
+        short = 't',
+        long = "timer",
+        value_name = "NAME",
+        global = true,
+        add = ArgValueCandidates::new(timer_name_candidates)
+    )]
+    pub timers: Vec<String>,
+    /// Separator between timers when showing several of them
+    #[arg(long = "timer-sep", value_name = "STRING")]
+    #[clap(default_value = DEFAULT_TIMER_SEPARATOR)]
+    pub timer_separator: String,
+    /// How to show the timer's progress
+    #[arg(
+        short = 'o',
+        long = "output",
+        value_name = "FORMAT",
+        value_enum,
+        default_value = "plain"
+    )]
+    pub output: OutputFormat,
+    /// Describe progress in words (eg. "focus, 14 minutes left") rather than with a progress bar
+    #[arg(long = "accessible", env = "TOMO_ACCESSIBLE")]
+    pub accessible: bool,
+    /// Scope the timer to the current tmux session (falls back to the global timer outside tmux)
+    #[arg(long = "tmux-scope", global = true, env = "TOMO_TMUX_SCOPE")]
+    pub tmux_scope: bool,
+    /// Fail if tomo's data file is missing or corrupt, instead of treating the timer as stopped
+    #[arg(long = "strict", global = true)]
+    pub strict: bool,
+    /// Act as if it's this time (RFC 3339); meant for tests and scripts
+    #[arg(
+        long = "now",
+        value_name = "TIME",
+        global = true,
+        hide = true,
+        env = NOW_ENV_VAR
+    )]
+    pub now: Option<DateTime<Utc>>,
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -52,9 +120,122 @@ pub enum Action {
         #[arg(short = 'e', long = "elapsed-mins", value_name = "NUM")]
         #[clap(default_value = "0")]
         elapsed_mins: u8,
+        /// Task the pomodoro is for
+        #[arg(
+            long = "task",
+            value_name = "LABEL",
+            add = ArgValueCandidates::new(task_candidates)
+        )]
+        task: Option<String>,
+        /// Tag to record the pomodoro with; can be repeated
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
     },
     /// Stop timer
     Stop,
     /// Start a break
     Break,
+    /// List all active timers
+    List,
+    /// Show the timer's state, times, task, and today's count, one per line
+    Status,
+    /// Print the script that sets up shell completions for tomo
+    Completions {
+        /// Shell to set up completions for
+        #[arg(value_enum)]
+        shell: CompletionShell,
+    },
+    /// Print tomo's man page
+    Man,
+    /// Export recorded pomodoros and breaks
+    Export {
+        /// Format to export in
+        #[arg(short = 'f', long = "format", value_enum, default_value = "csv")]
+        format: ExportFormat,
+        /// Only export sessions that started at or after this date (YYYY-MM-DD) or time (RFC 3339)
+        #[arg(long = "since", value_name = "DATE|TIME")]
+        since: Option<TimeBound>,
+        /// Only export sessions that started before this time, or on or before this date
+        #[arg(long = "until", value_name = "DATE|TIME")]
+        until: Option<TimeBound>,
+    },
+    /// Import pomodoros and breaks from another app, or from tomo's own export
+    Import {
+        /// Format of the file to import
+        #[arg(long = "from", value_name = "FORMAT", value_enum)]
+        from: ImportFormat,
+        /// File to import
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Read a session field from a CSV column with a different name (eg. "start=Started At");
+        /// can be repeated. Fields: start, end, duration_mins, kind, timer, planned_mins, task, tags
+        #[arg(long = "column", value_name = "FIELD=COLUMN")]
+        columns: Vec<ColumnMapping>,
+        /// Separator between tags in the CSV's tags column
+        #[arg(long = "tags-sep", value_name = "STRING")]
+        #[clap(default_value = DEFAULT_TAGS_SEPARATOR)]
+        tags_separator: String,
+    },
+    /// Show progress towards your goals, and how often you've met them
+    Stats,
+    /// Report on recorded pomodoros
+    #[command(group(ArgGroup::new("kind").required(true).args(["heatmap", "by"])))]
+    Report {
+        /// Show a calendar of pomodoros per day, and the hours of the day they were started in
+        #[arg(long = "heatmap")]
+        heatmap: bool,
+        /// Show pomodoros and minutes of focus per task or tag
+        #[arg(long = "by", value_name = "LABEL", value_enum)]
+        by: Option<ReportBy>,
+        /// Period to report on, when reporting by task or tag
+        #[arg(
+            long = "period",
+            value_name = "PERIOD",
+            value_enum,
+            default_value = "week"
+        )]
+        period: ReportPeriod,
+        /// What to sort by, when reporting by task or tag
+        #[arg(
+            long = "sort",
+            value_name = "COLUMN",
+            value_enum,
+            default_value = "minutes"
+        )]
+        sort: ReportSort,
+        /// Format to report in, when reporting by task or tag (CSV leaves out the total row)
+        #[arg(
+            short = 'f',
+            long = "format",
+            value_name = "FORMAT",
+            value_enum,
+            default_value = "table"
+        )]
+        format: ReportFormat,
+        /// Number of weeks to show in the heatmap
+        #[arg(
+            long = "weeks",
+            value_name = "NUM",
+            value_parser = clap::value_parser!(u16).range(1..=104)
+        )]
+        #[clap(default_value_t = DEFAULT_HEATMAP_WEEKS)]
+        weeks: u16,
+        /// When to colour the output
+        #[arg(
+            long = "color",
+            value_name = "WHEN",
+            value_enum,
+            default_value = "auto"
+        )]
+        color: ColorMode,
+    },
+    /// Show a full-screen view of the timer, with key bindings to start, break, and stop it
+    Tui,
+    /// Show the time left in big digits, with the progress bar underneath
+    Clock,
+    /// Check tomo's setup for problems (eg. timestamps in the future, after a clock jump)
+    Doctor,
+    /// Move the plain history log into a SQLite database, which tomo uses from then on
+    #[cfg(feature = "sqlite")]
+    Migrate,
 }
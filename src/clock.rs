@@ -0,0 +1,214 @@
+use crate::tui::{bar_blocks, draw_centered, full_screen, is_quit_key};
+use chrono::{DateTime, Utc};
+use crossterm::event::{self, Event, KeyEventKind};
+use crossterm::terminal;
+use std::time::Duration;
+use tomo::config::DisplayConfig;
+use tomo::state::TimerState;
+use tomo::store::Store;
+use tomo::time::TimeSource;
+use tomo::timer::Timer;
+use tomo::track::{POMODORO_MINS, format_clock, get_progress_bar};
+
+const REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+const GLYPH_HEIGHT: usize = 5;
+const MAX_SCALE: usize = 4;
+// lines shown below the digits: a blank line, the progress bar, another blank line, and a label
+const LINES_BELOW_DIGITS: usize = 4;
+const PLACEHOLDER_TIME: &str = "--:--";
+
+/// Returns the glyph for a character of a clock, as rows of cells; '#' cells are filled in.
+fn glyph(c: char) -> [&'static str; GLYPH_HEIGHT] {
+    match c {
+        '0' => ["###", "# #", "# #", "# #", "###"],
+        '1' => [" # ", "## ", " # ", " # ", "###"],
+        '2' => ["###", "  #", "###", "#  ", "###"],
+        '3' => ["###", "  #", "###", "  #", "###"],
+        '4' => ["# #", "# #", "###", "  #", "  #"],
+        '5' => ["###", "#  ", "###", "  #", "###"],
+        '6' => ["###", "#  ", "###", "# #", "###"],
+        '7' => ["###", "  #", "  #", "  #", "  #"],
+        '8' => ["###", "# #", "###", "# #", "###"],
+        '9' => ["###", "# #", "###", "  #", "###"],
+        ':' => [" ", "#", " ", "#", " "],
+        '-' => ["   ", "   ", "###", "   ", "   "],
+        _ => ["   ", "   ", "   ", "   ", "   "],
+    }
+}
+
+/// Returns the width (in cells) of some text rendered with big glyphs, at a scale of 1.
+fn big_text_width(text: &str) -> usize {
+    let glyphs_width = text.chars().map(|c| glyph(c)[0].len()).sum::<usize>();
+    glyphs_width + text.chars().count().saturating_sub(1)
+}
+
+/// Renders text (made up of digits, ':' and '-') in big block characters. Each cell of a glyph
+/// is `scale` rows tall and twice as many columns wide, so that it looks roughly square.
+fn render_big_text(text: &str, scale: usize) -> Vec<String> {
+    let mut lines = vec![];
+    for row in 0..GLYPH_HEIGHT {
+        let line = text
+            .chars()
+            .map(|c| {
+                glyph(c)[row]
+                    .chars()
+                    .map(|cell| if cell == '#' { "██" } else { "  " }.repeat(scale))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join(&"  ".repeat(scale));
+
+        // rows aren't trimmed, so that they stay aligned when centered
+        for _ in 0..scale {
+            lines.push(line.clone());
+        }
+    }
+
+    lines
+}
+
+/// Shows the time left in the current pomodoro in big digits, with the progress bar underneath,
+/// until the user quits. The clock is redrawn every second, and whenever the terminal is resized.
+pub fn run_clock(
+    store: &dyn Store,
+    timer: &Timer,
+    display_config: &DisplayConfig,
+    time: &TimeSource,
+) -> anyhow::Result<()> {
+    full_screen(|stdout| {
+        loop {
+            let state = store.read_state(timer, false)?;
+            let (width, height) = terminal::size()?;
+            let lines = clock_lines(&state, time.now(), display_config, width, height);
+            draw_centered(stdout, &lines, width, height)?;
+
+            if !event::poll(REDRAW_INTERVAL)? {
+                continue;
+            }
+
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && is_quit_key(&key)
+            {
+                return Ok(());
+            }
+        }
+    })
+}
+
+fn clock_lines(
+    state: &TimerState,
+    now: DateTime<Utc>,
+    display_config: &DisplayConfig,
+    width: u16,
+    height: u16,
+) -> Vec<String> {
+    let bar_config = DisplayConfig {
+        left_pad: String::new(),
+        right_pad: String::new(),
+        num_blocks: bar_blocks(width, display_config) as u8,
+        ..display_config.clone()
+    };
+
+    let (time, bar, label) = match state {
+        TimerState::Running(pomodoro) => {
+            let elapsed = now
+                .signed_duration_since(pomodoro.started_at)
+                .num_seconds()
+                .max(0);
+            let label = pomodoro.task.clone().unwrap_or(String::from("focus"));
+            (
+                format_clock(POMODORO_MINS * 60 - elapsed),
+                get_progress_bar(elapsed, &bar_config),
+                label,
+            )
+        }
+        TimerState::OnBreak { since } => (
+            since.map_or(String::from(PLACEHOLDER_TIME), |s| {
+                format_clock(now.signed_duration_since(s).num_seconds())
+            }),
+            display_config.break_msg.clone(),
+            String::from("break"),
+        ),
+        TimerState::Stopped { .. } => (
+            String::from(PLACEHOLDER_TIME),
+            get_progress_bar(0, &bar_config),
+            String::from("stopped"),
+        ),
+    };
+
+    // the biggest scale at which the clock still fits; if it doesn't fit at all, it's shown as
+    // plain text
+    let scale = (1..=MAX_SCALE).rev().find(|s| {
+        big_text_width(&time) * 2 * s <= width as usize
+            && GLYPH_HEIGHT * s + LINES_BELOW_DIGITS <= height as usize
+    });
+
+    let mut lines = match scale {
+        Some(s) => render_big_text(&time, s),
+        None => vec![time],
+    };
+    lines.extend([String::new(), bar, String::new(), label]);
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tomo::state::Pomodoro;
+
+    #[test]
+    fn big_text_is_rendered_with_blocks() {
+        // GIVEN
+        // WHEN
+        let got = render_big_text("1:0", 1);
+
+        // THEN
+        assert_eq!(
+            got,
+            vec![
+                "  ██        ██████",
+                "████    ██  ██  ██",
+                "  ██        ██  ██",
+                "  ██    ██  ██  ██",
+                "██████      ██████",
+            ]
+        );
+    }
+
+    #[test]
+    fn big_text_scales_up() {
+        // GIVEN
+        // WHEN
+        let got = render_big_text("7", 2);
+
+        // THEN
+        assert_eq!(got.len(), GLYPH_HEIGHT * 2);
+        assert_eq!(got[0], "████████████");
+        assert_eq!(got[2], "        ████");
+    }
+
+    #[test]
+    fn clock_scales_with_the_terminal() {
+        // GIVEN
+        let started_at = DateTime::parse_from_rfc3339("2025-01-06T09:00:00Z")
+            .expect("timestamp should've been parsed")
+            .to_utc();
+        let state = TimerState::Running(Pomodoro {
+            started_at,
+            task: Some(String::from("JIRA-123")),
+            tags: vec![],
+        });
+        let now = started_at + chrono::Duration::seconds(90);
+
+        // WHEN
+        let small = clock_lines(&state, now, &DisplayConfig::default(), 20, 10);
+        let big = clock_lines(&state, now, &DisplayConfig::default(), 200, 60);
+
+        // THEN
+        assert_eq!(small[0], "23:30");
+        assert_eq!(small[4], "JIRA-123");
+        assert_eq!(big.len(), GLYPH_HEIGHT * MAX_SCALE + LINES_BELOW_DIGITS);
+    }
+}
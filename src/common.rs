@@ -1,8 +1,24 @@
+/// Block shown for the part of a pomodoro that is still to go
 pub const DEFAULT_PENDING_BLOCK: &str = "▫";
+/// Block shown for the part of a pomodoro that is done
 pub const DEFAULT_COMPLETE_BLOCK: &str = "▪";
+/// Shown before the progress bar
 pub const DEFAULT_LEFT_PAD: &str = " ";
+/// Shown after the progress bar
 pub const DEFAULT_RIGHT_PAD: &str = " ";
+/// Shown between the blocks of the progress bar
 pub const DEFAULT_DELIMITER: &str = "";
+/// Number of blocks in the progress bar
 pub const DEFAULT_NUM_BLOCKS: u8 = 10;
+/// Shown once a pomodoro is done
 pub const DEFAULT_FINISHED_MSG: &str = "done";
+/// Shown during a break
 pub const DEFAULT_BREAK_MSG: &str = "\\o/";
+/// Shown between timers when showing several of them
+pub const DEFAULT_TIMER_SEPARATOR: &str = " | ";
+/// Shown when no timer is running
+pub const DEFAULT_IDLE_MSG: &str = "";
+/// Separator between tags in CSV exports and imports
+pub const DEFAULT_TAGS_SEPARATOR: &str = ",";
+/// Number of weeks shown in the report heatmap
+pub const DEFAULT_HEATMAP_WEEKS: u16 = 12;
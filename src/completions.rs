@@ -0,0 +1,73 @@
+use clap::ValueEnum;
+use clap_complete::CompletionCandidate;
+use clap_complete::env::{Bash, Elvish, EnvCompleter, Fish, Powershell, Zsh};
+use std::env;
+use std::io::Write;
+use std::path::PathBuf;
+use tomo::store::open_store;
+use tomo::timer::{default_data_file_path, list_timers};
+
+pub const COMPLETE_ENV_VAR: &str = "COMPLETE";
+pub const DATA_FILE_ENV_VAR: &str = "TOMO_DATA_FILE";
+pub const CONFIG_FILE_ENV_VAR: &str = "TOMO_CONFIG_FILE";
+const BIN_NAME: &str = "tomo";
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Elvish,
+    Fish,
+    Powershell,
+    Zsh,
+}
+
+/// Prints the script that registers tomo's completions with a shell. The script calls back into
+/// tomo while completing, which lets values like timer names be completed dynamically.
+pub fn print_completions(shell: CompletionShell) -> anyhow::Result<()> {
+    let completer: &dyn EnvCompleter = match shell {
+        CompletionShell::Bash => &Bash,
+        CompletionShell::Elvish => &Elvish,
+        CompletionShell::Fish => &Fish,
+        CompletionShell::Powershell => &Powershell,
+        CompletionShell::Zsh => &Zsh,
+    };
+
+    let mut stdout = std::io::stdout().lock();
+    completer.write_registration(COMPLETE_ENV_VAR, BIN_NAME, BIN_NAME, BIN_NAME, &mut stdout)?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// Since arguments aren't parsed while completing, a custom data file is only picked up via the
+/// environment.
+fn data_file_path() -> PathBuf {
+    env::var_os(DATA_FILE_ENV_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(default_data_file_path)
+}
+
+/// Returns the names of all timers that have a data file.
+pub fn timer_name_candidates() -> Vec<CompletionCandidate> {
+    list_timers(&data_file_path())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|t| CompletionCandidate::new(t.name))
+        .collect()
+}
+
+/// Returns the task labels recorded in the history log, most recently used first.
+pub fn task_candidates() -> Vec<CompletionCandidate> {
+    let sessions = open_store(&data_file_path())
+        .and_then(|store| store.read_sessions())
+        .unwrap_or_default();
+
+    let mut tasks: Vec<String> = vec![];
+    for task in sessions.into_iter().rev().filter_map(|s| s.task) {
+        if !tasks.contains(&task) {
+            tasks.push(task);
+        }
+    }
+
+    tasks.into_iter().map(CompletionCandidate::new).collect()
+}
@@ -1,17 +1,43 @@
 use crate::common::{
     DEFAULT_BREAK_MSG, DEFAULT_COMPLETE_BLOCK, DEFAULT_DELIMITER, DEFAULT_FINISHED_MSG,
-    DEFAULT_LEFT_PAD, DEFAULT_NUM_BLOCKS, DEFAULT_PENDING_BLOCK, DEFAULT_RIGHT_PAD,
+    DEFAULT_IDLE_MSG, DEFAULT_LEFT_PAD, DEFAULT_NUM_BLOCKS, DEFAULT_PENDING_BLOCK,
+    DEFAULT_RIGHT_PAD, DEFAULT_TIMER_SEPARATOR,
 };
+use anyhow::Context;
+use dirs::config_dir;
+use serde::Deserialize;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
 
+const CONFIG_DIR: &str = "tomo";
+const CONFIG_FILE: &str = "config.toml";
+
+/// How to show timers' progress; the defaults are the ones in [`crate::common`].
+#[derive(Clone)]
 pub struct DisplayConfig {
+    /// Block for the part of a pomodoro that is still to go
     pub pending_block: String,
+    /// Block for the part of a pomodoro that is done
     pub complete_block: String,
+    /// Shown before the progress bar (and the messages)
     pub left_pad: String,
+    /// Shown after the progress bar (and the messages)
     pub right_pad: String,
+    /// Shown between blocks
     pub delimiter: String,
+    /// Number of blocks in the progress bar
     pub num_blocks: u8,
+    /// Shown once a pomodoro is done
     pub finished_msg: String,
+    /// Shown during a break
     pub break_msg: String,
+    /// Shown when no timer is running
+    pub idle_msg: String,
+    /// Shown between timers when showing several of them
+    pub timer_separator: String,
+    /// Describe progress in words rather than with a progress bar
+    pub accessible: bool,
 }
 
 impl Default for DisplayConfig {
@@ -25,6 +51,89 @@ impl Default for DisplayConfig {
             num_blocks: DEFAULT_NUM_BLOCKS,
             finished_msg: DEFAULT_FINISHED_MSG.into(),
             break_msg: DEFAULT_BREAK_MSG.into(),
+            idle_msg: DEFAULT_IDLE_MSG.into(),
+            timer_separator: DEFAULT_TIMER_SEPARATOR.into(),
+            accessible: false,
         }
     }
 }
+
+/// Settings read from tomo's config file.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Number of pomodoros to aim for each day
+    pub daily_goal: Option<u32>,
+    /// Number of pomodoros to aim for each week (weeks start on Monday)
+    pub weekly_goal: Option<u32>,
+    /// Message to show when no timer is running, unless --idle-msg is passed
+    pub idle_msg: Option<String>,
+    /// Describe progress in words rather than with a progress bar (eg. for screen readers)
+    #[serde(default)]
+    pub accessible: bool,
+}
+
+/// Returns the config file used when none is specified: <YOUR_CONFIG_DIR>/tomo/config.toml
+pub fn default_config_file_path() -> PathBuf {
+    let user_config_dir = config_dir().unwrap_or(PathBuf::from("."));
+
+    user_config_dir
+        .join(PathBuf::from(CONFIG_DIR))
+        .join(PathBuf::from(CONFIG_FILE))
+}
+
+/// Reads tomo's config file; a missing config file is treated as an empty one.
+pub fn read_config(file_path: &Path) -> anyhow::Result<Config> {
+    let contents = match fs::read_to_string(file_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(e).context("couldn't read tomo's config file"),
+    };
+
+    let config: Config = toml::from_str(&contents).context("couldn't parse tomo's config file")?;
+
+    if config.daily_goal == Some(0) || config.weekly_goal == Some(0) {
+        return Err(anyhow::anyhow!(
+            "goals in tomo's config file need to be greater than 0"
+        ));
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn goals_are_read_from_config() {
+        // GIVEN
+        let contents = "daily_goal = 8\nweekly_goal = 35\n";
+
+        // WHEN
+        let got: Config = toml::from_str(contents).expect("config should've been parsed");
+
+        // THEN
+        assert_eq!(
+            got,
+            Config {
+                daily_goal: Some(8),
+                weekly_goal: Some(35),
+                idle_msg: None,
+                accessible: false,
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_config_keys_are_rejected() {
+        // GIVEN
+        let contents = "daily_gaol = 8\n";
+
+        // WHEN
+        let got = toml::from_str::<Config>(contents);
+
+        // THEN
+        assert!(got.is_err());
+    }
+}
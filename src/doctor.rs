@@ -0,0 +1,487 @@
+use chrono::{DateTime, Utc};
+use std::fs::{self, File, OpenOptions};
+use std::io::ErrorKind;
+use std::path::Path;
+use tomo::config::{default_config_file_path, read_config};
+use tomo::history::{Session, history_file_path};
+use tomo::lock::{is_locked, lock_file_path};
+use tomo::state::TimerState;
+use tomo::store::open_store_read_only;
+use tomo::timer::{default_data_file_path, list_timers};
+use tomo::tmux;
+use tomo::track::{CLOCK_SKEW_TOLERANCE_SECS, format_duration};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Problem,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Warning => "warning",
+            Self::Problem => "problem",
+        }
+    }
+}
+
+/// The outcome of one of tomo doctor's checks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Checks where the data file is, and that tomo can read and write it.
+fn check_data_file(data_file_path: &Path) -> Check {
+    let is_default = data_file_path == default_data_file_path();
+    let (mut status, source) = match (is_default, dirs::data_dir()) {
+        (true, Some(_)) => (CheckStatus::Ok, "default"),
+        // this is the "." fallback in default_data_file_path
+        (true, None) => (
+            CheckStatus::Warning,
+            "default, relative to the current directory since this platform has no data directory",
+        ),
+        (false, _) => (CheckStatus::Ok, "set via --data-file or TOMO_DATA_FILE"),
+    };
+    let path = format!("{} ({source})", data_file_path.display());
+    let dir = match data_file_path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+
+    match fs::metadata(dir) {
+        // tomo creates the default data directory when it first needs it
+        Err(e) if e.kind() == ErrorKind::NotFound && is_default => {
+            return Check::new(
+                "data file",
+                status,
+                format!("{path}; doesn't exist yet, and neither does its directory"),
+            );
+        }
+        Err(e) => {
+            return Check::new(
+                "data file",
+                CheckStatus::Problem,
+                format!("{path}; its directory can't be accessed: {e}"),
+            );
+        }
+        Ok(_) => {}
+    }
+
+    // the data file is replaced (rather than modified in place) on every write, so tomo needs to
+    // be able to create files next to it
+    if let Err(e) = probe_dir_writable(dir) {
+        return Check::new(
+            "data file",
+            CheckStatus::Problem,
+            format!("{path}; its directory isn't writable: {e}"),
+        );
+    }
+
+    let metadata = match fs::metadata(data_file_path) {
+        Ok(m) => m,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Check::new(
+                "data file",
+                status,
+                format!("{path}; doesn't exist yet, it's written once a timer is started"),
+            );
+        }
+        Err(e) => {
+            return Check::new(
+                "data file",
+                CheckStatus::Problem,
+                format!("{path}; can't be accessed: {e}"),
+            );
+        }
+    };
+
+    let detail = if !metadata.is_file() {
+        status = CheckStatus::Problem;
+        String::from("isn't a file")
+    } else if let Err(e) = File::open(data_file_path) {
+        status = CheckStatus::Problem;
+        format!("can't be read: {e}")
+    } else if let Err(e) = OpenOptions::new().append(true).open(data_file_path) {
+        status = CheckStatus::Problem;
+        format!("can't be written: {e}")
+    } else {
+        format!("readable and writable{}", describe_mode(&metadata))
+    };
+
+    Check::new("data file", status, format!("{path}; {detail}"))
+}
+
+/// Creates (and removes) a file in `dir`, since permission bits alone don't say whether the current
+/// user can write there.
+fn probe_dir_writable(dir: &Path) -> std::io::Result<()> {
+    let probe_path = dir.join(format!(".tomo.doctor-{}", std::process::id()));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe_path)?;
+    fs::remove_file(&probe_path)
+}
+
+#[cfg(unix)]
+fn describe_mode(metadata: &fs::Metadata) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    format!(" (mode {:o})", metadata.permissions().mode() & 0o777)
+}
+
+#[cfg(not(unix))]
+fn describe_mode(_metadata: &fs::Metadata) -> String {
+    String::new()
+}
+
+/// Parses the state of every timer that has a data file. The data files are read directly (rather
+/// than through the store), since reading a corrupt one through the store resets it.
+fn check_state(data_file_path: &Path) -> (Check, Vec<(String, TimerState)>) {
+    let timers = match list_timers(data_file_path) {
+        Ok(t) => t,
+        Err(e) => {
+            return (
+                Check::new("state", CheckStatus::Problem, format!("{e:#}")),
+                vec![],
+            );
+        }
+    };
+
+    let mut status = CheckStatus::Ok;
+    let mut descriptions = vec![];
+    let mut states = vec![];
+    for timer in timers {
+        let parsed = fs::read(&timer.data_file_path)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| TimerState::parse(&contents));
+
+        match parsed {
+            Ok(state) => {
+                descriptions.push(format!("{}: {}", timer.name, describe_state(&state)));
+                states.push((timer.name, state));
+            }
+            Err(e) => {
+                status = CheckStatus::Problem;
+                descriptions.push(format!("{}: couldn't be parsed ({e:#})", timer.name));
+            }
+        }
+    }
+
+    if descriptions.is_empty() {
+        descriptions.push(String::from("no timer has a state yet"));
+    }
+
+    (Check::new("state", status, descriptions.join("; ")), states)
+}
+
+fn describe_state(state: &TimerState) -> String {
+    match state {
+        TimerState::Stopped { since: None } => String::from("stopped"),
+        TimerState::Stopped { since: Some(at) } => format!("stopped at {}", at.to_rfc3339()),
+        TimerState::OnBreak { since: None } => String::from("on a break"),
+        TimerState::OnBreak { since: Some(at) } => {
+            format!("on a break since {}", at.to_rfc3339())
+        }
+        TimerState::Running(pomodoro) => match &pomodoro.task {
+            Some(task) => format!(
+                "running since {}, on \"{task}\"",
+                pomodoro.started_at.to_rfc3339()
+            ),
+            None => format!("running since {}", pomodoro.started_at.to_rfc3339()),
+        },
+    }
+}
+
+/// Reads the history log (or the SQLite database, once there is one), without writing to either.
+fn check_history(data_file_path: &Path) -> (Check, Vec<Session>) {
+    match open_store_read_only(data_file_path).and_then(|store| store.read_sessions()) {
+        Ok(sessions) => (
+            Check::new(
+                "history",
+                CheckStatus::Ok,
+                format!("{} session(s) recorded", sessions.len()),
+            ),
+            sessions,
+        ),
+        Err(e) => (
+            Check::new("history", CheckStatus::Problem, format!("{e:#}")),
+            vec![],
+        ),
+    }
+}
+
+/// Checks where the config file is, and that it can be parsed.
+fn check_config(config_file_path: &Path) -> Check {
+    let source = if config_file_path == default_config_file_path() {
+        "default"
+    } else {
+        "set via --config-file or TOMO_CONFIG_FILE"
+    };
+    let path = format!("{} ({source})", config_file_path.display());
+
+    if !config_file_path.exists() {
+        return Check::new(
+            "config",
+            CheckStatus::Ok,
+            format!("{path}; doesn't exist, so the defaults are used"),
+        );
+    }
+
+    match read_config(config_file_path) {
+        Ok(_) => Check::new("config", CheckStatus::Ok, format!("{path}; parsed")),
+        Err(e) => Check::new("config", CheckStatus::Problem, format!("{path}; {e:#}")),
+    }
+}
+
+/// Checks whether another tomo process holds the lock on any timer's data file, or on the
+/// history log. A lock that stays held makes every other tomo process time out.
+fn check_lock(data_file_path: &Path) -> Check {
+    let timers = match list_timers(data_file_path) {
+        Ok(t) => t,
+        Err(e) => return Check::new("lock", CheckStatus::Problem, format!("{e:#}")),
+    };
+
+    let mut held = vec![];
+    let locked_files = timers
+        .into_iter()
+        .map(|t| t.data_file_path)
+        .chain([history_file_path(data_file_path)]);
+    for path in locked_files {
+        match is_locked(&path) {
+            Ok(true) => held.push(lock_file_path(&path)),
+            Ok(false) => {}
+            Err(e) => {
+                return Check::new(
+                    "lock",
+                    CheckStatus::Problem,
+                    format!("{}; {e:#}", lock_file_path(&path).display()),
+                );
+            }
+        }
+    }
+
+    if held.is_empty() {
+        return Check::new("lock", CheckStatus::Ok, "not held by any tomo process");
+    }
+
+    Check::new(
+        "lock",
+        CheckStatus::Warning,
+        format!(
+            "held by another tomo process: {}; if it stays held, that process may be stuck",
+            held.iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    )
+}
+
+/// Checks whether tomo runs inside tmux, and if so, which session it sees.
+fn check_tmux() -> Check {
+    if std::env::var_os(tmux::TMUX_ENV_VAR).is_none_or(|v| v.is_empty()) {
+        return Check::new("tmux", CheckStatus::Ok, "not running inside tmux");
+    }
+
+    match tmux::current_session() {
+        Some(session) => Check::new(
+            "tmux",
+            CheckStatus::Ok,
+            format!(
+                "running inside tmux session \"{session}\" (its timer with --tmux-scope: {})",
+                tmux::session_timer_name(&session)
+            ),
+        ),
+        None => Check::new(
+            "tmux",
+            CheckStatus::Warning,
+            "TMUX is set, but tmux couldn't be asked for the current session; --tmux-scope falls back to the default timer",
+        ),
+    }
+}
+
+/// Checks that no timer's state, and no session in the history log, is in the future. That
+/// happens when a clock jumps (eg. after an NTP sync), or when the data file is synced from a
+/// machine whose clock is ahead.
+pub fn check_clock(
+    states: &[(String, TimerState)],
+    sessions: &[Session],
+    now: DateTime<Utc>,
+) -> Check {
+    let cutoff = now + chrono::Duration::seconds(CLOCK_SKEW_TOLERANCE_SECS);
+    let mut findings = vec![];
+
+    for (name, state) in states {
+        let (verb, at) = match state {
+            TimerState::Stopped { since: Some(at) } => ("stopped", *at),
+            TimerState::OnBreak { since: Some(at) } => ("went on a break", *at),
+            TimerState::Running(pomodoro) => ("started", pomodoro.started_at),
+            TimerState::Stopped { since: None } | TimerState::OnBreak { since: None } => continue,
+        };
+
+        if at > cutoff {
+            findings.push(format!(
+                "timer \"{name}\" {verb} {} in the future",
+                format_duration((at - now).num_seconds())
+            ));
+        }
+    }
+
+    let future_ends = sessions
+        .iter()
+        .map(|s| s.end)
+        .filter(|end| *end > cutoff)
+        .collect::<Vec<_>>();
+    if let Some(latest) = future_ends.iter().max() {
+        findings.push(format!(
+            "{} session(s) in the history log end in the future (the latest in {})",
+            future_ends.len(),
+            format_duration((*latest - now).num_seconds())
+        ));
+    }
+
+    if findings.is_empty() {
+        return Check::new(
+            "clock",
+            CheckStatus::Ok,
+            format!("nothing is in the future (it's {})", now.to_rfc3339()),
+        );
+    }
+
+    Check::new(
+        "clock",
+        CheckStatus::Problem,
+        format!(
+            "{}; check this machine's clock (it's {}), and the clocks of machines the data file is synced from",
+            findings.join("; "),
+            now.to_rfc3339()
+        ),
+    )
+}
+
+/// Runs all checks and prints their outcomes. Fails if any of them found a problem.
+///
+/// Nothing is modified, so that the checks see the setup as it is; the only file created is a
+/// short-lived `.tomo.doctor-<pid>` in the data directory, to check that it's writable.
+pub fn run_doctor(
+    data_file_path: &Path,
+    config_file_path: &Path,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let (state_check, states) = check_state(data_file_path);
+    let (history_check, sessions) = check_history(data_file_path);
+    let checks = vec![
+        check_data_file(data_file_path),
+        state_check,
+        history_check,
+        check_config(config_file_path),
+        check_lock(data_file_path),
+        check_tmux(),
+        check_clock(&states, &sessions, now),
+    ];
+
+    let width = checks
+        .iter()
+        .map(|c| c.name.len())
+        .max()
+        .unwrap_or_default();
+    for check in &checks {
+        println!(
+            "{:<7}  {:<width$}  {}",
+            check.status.as_str(),
+            check.name,
+            check.detail
+        );
+    }
+
+    let problems = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Problem)
+        .count();
+    if problems > 0 {
+        return Err(anyhow::anyhow!("tomo doctor found {problems} problem(s)"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tomo::state::Pomodoro;
+
+    fn time(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339)
+            .expect("timestamp should've been parsed")
+            .to_utc()
+    }
+
+    fn running_since(rfc3339: &str) -> Vec<(String, TimerState)> {
+        vec![(
+            String::from("default"),
+            TimerState::Running(Pomodoro {
+                started_at: time(rfc3339),
+                task: None,
+                tags: vec![],
+            }),
+        )]
+    }
+
+    #[test]
+    fn check_clock_flags_start_times_in_the_future() {
+        // GIVEN
+        let states = running_since("2025-01-06T11:05:00Z");
+
+        // WHEN
+        let got = check_clock(&states, &[], time("2025-01-06T09:00:00Z"));
+
+        // THEN
+        assert_eq!(got.status, CheckStatus::Problem);
+        insta::assert_snapshot!(got.detail, @r#"timer "default" started 2h 5m in the future; check this machine's clock (it's 2025-01-06T09:00:00+00:00), and the clocks of machines the data file is synced from"#);
+    }
+
+    #[test]
+    fn check_clock_tolerates_small_differences() {
+        // GIVEN
+        let states = running_since("2025-01-06T09:00:30Z");
+
+        // WHEN
+        let got = check_clock(&states, &[], time("2025-01-06T09:00:00Z"));
+
+        // THEN
+        assert_eq!(got.status, CheckStatus::Ok);
+    }
+
+    #[test]
+    fn check_lock_flags_a_held_history_lock() {
+        // GIVEN
+        let dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let data_file_path = dir.path().join(".tomo");
+        let history_file_path = history_file_path(&data_file_path);
+        let _lock = tomo::lock::DataFileLock::acquire(&history_file_path)
+            .expect("lock should've been acquired");
+
+        // WHEN
+        let got = check_lock(&data_file_path);
+
+        // THEN
+        assert_eq!(got.status, CheckStatus::Warning);
+        assert!(got.detail.contains(".tomo.history.lock"));
+    }
+}
@@ -0,0 +1,251 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::Write;
+use tomo::history::{Session, SessionKind, SessionQuery, TimeBound};
+use tomo::store::Store;
+
+const TAGS_SEPARATOR: &str = ",";
+const ICS_PRODID: &str = "-//dhth//tomo//EN";
+const ICS_MAX_LINE_LEN: usize = 75;
+const CSV_HEADER: [&str; 9] = [
+    "start",
+    "end",
+    "kind",
+    "timer",
+    "planned_mins",
+    "actual_mins",
+    "completed",
+    "task",
+    "tags",
+];
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Jsonl,
+    Ics,
+}
+
+/// A session as it's exported; timestamps are formatted the same way as in tomo's data file.
+#[derive(Debug, Serialize)]
+struct ExportRecord {
+    start: String,
+    end: String,
+    kind: &'static str,
+    timer: String,
+    planned_mins: Option<i64>,
+    actual_mins: i64,
+    completed: bool,
+    task: Option<String>,
+    tags: Vec<String>,
+}
+
+impl From<Session> for ExportRecord {
+    fn from(session: Session) -> Self {
+        Self {
+            start: session.start.to_rfc3339(),
+            end: session.end.to_rfc3339(),
+            kind: session.kind.as_str(),
+            actual_mins: session.actual_seconds() / 60,
+            completed: session.is_completed(),
+            timer: session.timer,
+            planned_mins: session.planned_mins,
+            task: session.task,
+            tags: session.tags,
+        }
+    }
+}
+
+pub fn export_history(
+    store: &dyn Store,
+    format: ExportFormat,
+    since: Option<&TimeBound>,
+    until: Option<&TimeBound>,
+    timers: &[String],
+) -> anyhow::Result<()> {
+    let sessions = store.query_sessions(&SessionQuery {
+        timers: timers.to_vec(),
+        ..SessionQuery::between(since, until)
+    })?;
+
+    let mut stdout = std::io::stdout().lock();
+    match format {
+        ExportFormat::Csv => write_csv(&to_records(sessions), &mut stdout)?,
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut stdout, &to_records(sessions))
+                .context("couldn't serialize sessions")?;
+            writeln!(stdout)?;
+        }
+        ExportFormat::Jsonl => {
+            for record in &to_records(sessions) {
+                serde_json::to_writer(&mut stdout, record).context("couldn't serialize session")?;
+                writeln!(stdout)?;
+            }
+        }
+        ExportFormat::Ics => write_ics(&sessions, &mut stdout)?,
+    }
+    stdout.flush()?;
+
+    Ok(())
+}
+
+fn to_records(sessions: Vec<Session>) -> Vec<ExportRecord> {
+    sessions.into_iter().map(ExportRecord::from).collect()
+}
+
+fn write_csv(records: &[ExportRecord], writer: &mut dyn Write) -> anyhow::Result<()> {
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    csv_writer.write_record(CSV_HEADER)?;
+
+    for record in records {
+        csv_writer.write_record([
+            record.start.as_str(),
+            record.end.as_str(),
+            record.kind,
+            record.timer.as_str(),
+            &record
+                .planned_mins
+                .map(|p| p.to_string())
+                .unwrap_or_default(),
+            &record.actual_mins.to_string(),
+            &record.completed.to_string(),
+            record.task.as_deref().unwrap_or_default(),
+            &record.tags.join(TAGS_SEPARATOR),
+        ])?;
+    }
+
+    csv_writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes sessions as an iCalendar file (RFC 5545) with one VEVENT per session, so that they can
+/// be imported into calendar apps.
+fn write_ics(sessions: &[Session], writer: &mut dyn Write) -> anyhow::Result<()> {
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        format!("PRODID:{ICS_PRODID}"),
+        String::from("CALSCALE:GREGORIAN"),
+    ];
+
+    for session in sessions {
+        let completed = session.is_completed();
+        let label = match (&session.task, session.kind) {
+            (Some(task), _) => task.as_str(),
+            (None, SessionKind::Focus) => "Pomodoro",
+            (None, SessionKind::Break) => "Break",
+        };
+        let summary = if completed {
+            label.to_string()
+        } else {
+            format!("{label} (interrupted)")
+        };
+
+        let actual_mins = session.actual_seconds() / 60;
+        let mut description = match session.planned_mins {
+            Some(p) => format!("{actual_mins} of {p} mins on timer \"{}\"", session.timer),
+            None => format!("{actual_mins} mins on timer \"{}\"", session.timer),
+        };
+        if !completed {
+            description.push_str(" (interrupted)");
+        }
+
+        lines.push(String::from("BEGIN:VEVENT"));
+        lines.push(format!(
+            "UID:{}-{}-{}@tomo",
+            ics_time(&session.start),
+            session.timer,
+            session.kind.as_str()
+        ));
+        // the session's end is used rather than the time of exporting, so that exporting the
+        // same sessions again yields the same file
+        lines.push(format!("DTSTAMP:{}", ics_time(&session.end)));
+        lines.push(format!("DTSTART:{}", ics_time(&session.start)));
+        lines.push(format!("DTEND:{}", ics_time(&session.end)));
+        lines.push(format!("SUMMARY:{}", ics_escape(&summary)));
+        lines.push(format!("DESCRIPTION:{}", ics_escape(&description)));
+        if !session.tags.is_empty() {
+            let categories = session
+                .tags
+                .iter()
+                .map(|t| ics_escape(t))
+                .collect::<Vec<_>>()
+                .join(",");
+            lines.push(format!("CATEGORIES:{categories}"));
+        }
+        lines.push(String::from("TRANSP:OPAQUE"));
+        lines.push(format!(
+            "X-TOMO-COMPLETED:{}",
+            if completed { "TRUE" } else { "FALSE" }
+        ));
+        lines.push(String::from("END:VEVENT"));
+    }
+
+    lines.push(String::from("END:VCALENDAR"));
+
+    for line in lines {
+        write!(writer, "{}\r\n", ics_fold(&line))?;
+    }
+
+    Ok(())
+}
+
+fn ics_time(time: &DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// lines longer than 75 octets need to be folded, with continuation lines starting with a space
+fn ics_fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_len = 0;
+
+    for c in line.chars() {
+        if line_len + c.len_utf8() > ICS_MAX_LINE_LEN {
+            folded.push_str("\r\n ");
+            line_len = 1;
+        }
+        folded.push(c);
+        line_len += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ics_fold_splits_long_lines() {
+        // GIVEN
+        let line = format!("SUMMARY:{}", "ü".repeat(40));
+
+        // WHEN
+        let got = ics_fold(&line);
+
+        // THEN
+        assert!(got.split("\r\n").all(|l| l.len() <= ICS_MAX_LINE_LEN));
+        assert_eq!(got.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn ics_escape_escapes_special_characters() {
+        // GIVEN
+        // WHEN
+        let got = ics_escape("a, b; c\\d\ne");
+
+        // THEN
+        assert_eq!(got, r"a\, b\; c\\d\ne");
+    }
+}
@@ -0,0 +1,77 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Writes contents to a file such that readers either see the old contents or the new ones, never
+/// a partially written file. The contents are written to a temporary file in the same directory,
+/// synced to disk, and then renamed over the target.
+pub fn write_atomically(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let tmp_path = temp_path_for(path);
+
+    let result = write_and_sync(&tmp_path, contents).and_then(|_| fs::rename(&tmp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+        return result;
+    }
+
+    sync_parent_dir(path);
+
+    Ok(())
+}
+
+fn temp_path_for(path: &Path) -> PathBuf {
+    let mut file_name = path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    file_name.push(format!(".tmp-{}", std::process::id()));
+
+    path.with_file_name(file_name)
+}
+
+fn write_and_sync(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+// syncing the directory makes the rename itself durable; this isn't supported everywhere (eg.
+// on windows), and the write has already succeeded by this point, so errors are ignored
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(dir) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        let _ = fs::File::open(dir).and_then(|d| d.sync_all());
+    }
+
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn write_atomically_replaces_contents_and_leaves_no_temp_files() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let path = dir.path().join(".tomo");
+        fs::write(&path, "stop").expect("file should've been written");
+
+        // WHEN
+        write_atomically(&path, b"break").expect("file should've been written atomically");
+
+        // THEN
+        let contents = fs::read_to_string(&path).expect("file should've been read");
+        assert_eq!(contents, "break");
+        let num_entries = fs::read_dir(dir.path())
+            .expect("directory should've been read")
+            .count();
+        assert_eq!(num_entries, 1);
+    }
+}
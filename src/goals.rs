@@ -0,0 +1,161 @@
+use crate::config::{Config, DisplayConfig};
+use crate::history::{Session, SessionKind, SessionQuery, TimeBound};
+use crate::store::Store;
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Utc};
+use std::collections::BTreeMap;
+
+const TODAY_PLACEHOLDER: &str = "{today}";
+const GOAL_PLACEHOLDER: &str = "{goal}";
+const WEEK_PLACEHOLDER: &str = "{week}";
+const WEEKLY_GOAL_PLACEHOLDER: &str = "{weekly_goal}";
+const PLACEHOLDERS: [&str; 4] = [
+    TODAY_PLACEHOLDER,
+    GOAL_PLACEHOLDER,
+    WEEK_PLACEHOLDER,
+    WEEKLY_GOAL_PLACEHOLDER,
+];
+
+/// Returns the number of completed pomodoros for each (local) day that has any. Pomodoros from
+/// all timers count towards goals.
+pub fn pomodoros_per_day(sessions: &[Session]) -> BTreeMap<NaiveDate, u32> {
+    let mut counts = BTreeMap::new();
+    for session in sessions {
+        if session.kind != SessionKind::Focus || !session.is_completed() {
+            continue;
+        }
+
+        *counts.entry(local_date(&session.start)).or_default() += 1;
+    }
+
+    counts
+}
+
+/// Returns the number of completed pomodoros in the week (starting on Monday) that contains `date`.
+pub fn pomodoros_in_week(per_day: &BTreeMap<NaiveDate, u32>, date: NaiveDate) -> u32 {
+    let start = week_start(date);
+    let end = start + Days::new(7);
+
+    per_day.range(start..end).map(|(_, c)| c).sum()
+}
+
+/// Returns the (local) day a time falls on.
+pub fn local_date(time: &DateTime<Utc>) -> NaiveDate {
+    time.with_timezone(&Local).date_naive()
+}
+
+/// Returns the Monday of the week that contains `date`.
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday() as u64)
+}
+
+/// Replaces goal placeholders in the messages shown in the status bar with the number of
+/// pomodoros completed today/this week, and the goals for them ("-" if not set). The history log
+/// is only read if a placeholder is actually used.
+pub fn fill_goal_placeholders(
+    display_config: &mut DisplayConfig,
+    store: &dyn Store,
+    now: DateTime<Utc>,
+    config: &Config,
+) -> anyhow::Result<()> {
+    if !messages(display_config)
+        .iter()
+        .any(|m| PLACEHOLDERS.iter().any(|p| m.contains(p)))
+    {
+        return Ok(());
+    }
+
+    let today = local_date(&now);
+    let per_day = pomodoros_per_day(&store.query_sessions(&SessionQuery {
+        kind: Some(SessionKind::Focus),
+        ..SessionQuery::between(Some(&TimeBound::Date(week_start(today))), None)
+    })?);
+    let goal_or_dash = |g: Option<u32>| g.map_or(String::from("-"), |g| g.to_string());
+
+    replace_placeholders(
+        display_config,
+        [
+            (
+                TODAY_PLACEHOLDER,
+                per_day.get(&today).copied().unwrap_or_default().to_string(),
+            ),
+            (GOAL_PLACEHOLDER, goal_or_dash(config.daily_goal)),
+            (
+                WEEK_PLACEHOLDER,
+                pomodoros_in_week(&per_day, today).to_string(),
+            ),
+            (WEEKLY_GOAL_PLACEHOLDER, goal_or_dash(config.weekly_goal)),
+        ],
+    );
+
+    Ok(())
+}
+
+/// Replaces every goal placeholder with "-", eg. when the history log can't be read.
+pub fn clear_goal_placeholders(display_config: &mut DisplayConfig) {
+    replace_placeholders(display_config, PLACEHOLDERS.map(|p| (p, String::from("-"))));
+}
+
+fn replace_placeholders(display_config: &mut DisplayConfig, values: [(&str, String); 4]) {
+    for message in messages(display_config) {
+        for (placeholder, value) in &values {
+            *message = message.replace(placeholder, value);
+        }
+    }
+}
+
+/// The messages shown in the status bar, which can contain goal placeholders.
+fn messages(display_config: &mut DisplayConfig) -> [&mut String; 5] {
+    [
+        &mut display_config.left_pad,
+        &mut display_config.right_pad,
+        &mut display_config.finished_msg,
+        &mut display_config.break_msg,
+        &mut display_config.idle_msg,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_completed_pomodoros_count_towards_goals() {
+        // GIVEN
+        let start = Local::now().to_utc();
+        let session = |kind, mins| Session {
+            timer: String::from("default"),
+            kind,
+            start,
+            end: start + chrono::Duration::minutes(mins),
+            planned_mins: (kind == SessionKind::Focus).then_some(25),
+            task: None,
+            tags: vec![],
+        };
+        let sessions = vec![
+            session(SessionKind::Focus, 25),
+            session(SessionKind::Focus, 10),
+            session(SessionKind::Break, 5),
+        ];
+
+        // WHEN
+        let got = pomodoros_per_day(&sessions);
+
+        // THEN
+        assert_eq!(got.get(&local_date(&start)), Some(&1));
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        // GIVEN
+        let sunday = NaiveDate::from_ymd_opt(2025, 1, 12).expect("date should be valid");
+
+        // WHEN
+        let got = week_start(sunday);
+
+        // THEN
+        assert_eq!(
+            got,
+            NaiveDate::from_ymd_opt(2025, 1, 6).expect("date should be valid")
+        );
+    }
+}
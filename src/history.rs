@@ -0,0 +1,469 @@
+use anyhow::Context;
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const HISTORY_FILE_SUFFIX: &str = ".history";
+
+/// Whether a session was a pomodoro or a break.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionKind {
+    /// A pomodoro
+    Focus,
+    /// A break
+    Break,
+}
+
+impl SessionKind {
+    /// Returns the kind's name, as written to the history log.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionKind::Focus => "focus",
+            SessionKind::Break => "break",
+        }
+    }
+}
+
+/// A finished pomodoro or break, as recorded in the history log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// Name of the timer the session was recorded for
+    pub timer: String,
+    /// Whether it was a pomodoro or a break
+    pub kind: SessionKind,
+    /// When it started
+    pub start: DateTime<Utc>,
+    /// When it ended (early, if it was stopped before its planned length)
+    pub end: DateTime<Utc>,
+    /// How long it was meant to last; breaks have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub planned_mins: Option<i64>,
+    /// What was worked on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    /// Labels for grouping sessions in reports
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Session {
+    /// Returns how long the session actually lasted.
+    pub fn actual_seconds(&self) -> i64 {
+        self.end.signed_duration_since(self.start).num_seconds()
+    }
+
+    /// Whether the session ran for as long as planned; sessions without a plan (ie, breaks)
+    /// are always complete.
+    pub fn is_completed(&self) -> bool {
+        self.planned_mins
+            .is_none_or(|p| self.actual_seconds() >= p * 60)
+    }
+}
+
+/// Returns the history log that sits next to a data file (eg. `.tomo.history`). It's shared by
+/// all timers, since named timers live next to the default timer's data file.
+pub fn history_file_path(base_data_file: &Path) -> PathBuf {
+    let mut file_name = base_data_file
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    file_name.push(HISTORY_FILE_SUFFIX);
+
+    base_data_file.with_file_name(file_name)
+}
+
+/// Appends a session to the history log, one JSON object per line. Each session is written with
+/// a single append, so concurrent writers (eg. two timers stopping at once) don't interleave.
+pub fn append_session(file_path: &Path, session: &Session) -> anyhow::Result<()> {
+    append_sessions(file_path, std::slice::from_ref(session))
+}
+
+/// Appends several sessions to the history log in one go; callers are expected to hold the
+/// history log's lock. A last line that was cut off (see [`read_sessions`]) is dropped first, so
+/// that the new sessions don't end up glued to it.
+pub fn append_sessions(file_path: &Path, sessions: &[Session]) -> anyhow::Result<()> {
+    // a last line without a newline was cut off, unless it parses (eg. it was added by hand)
+    let mut lines = String::new();
+    if !ends_with_newline(file_path)? && !drop_cut_off_line(file_path)? {
+        lines.push('\n');
+    }
+    for session in sessions {
+        lines.push_str(&serde_json::to_string(session).context("couldn't serialize session")?);
+        lines.push('\n');
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)
+        .context("couldn't open tomo's history file")?;
+
+    // without syncing, a crash can still cut the line short after the write has returned
+    file.write_all(lines.as_bytes())
+        .and_then(|_| file.sync_data())
+        .context("couldn't write to tomo's history file")
+}
+
+/// Reads all sessions from the history log, in the order they were recorded. A missing history
+/// log is treated as an empty one.
+///
+/// A last line that can't be parsed (eg. one cut off by a crash or a full disk while it was
+/// being appended) is skipped with a warning, rather than breaking everything that reads the
+/// history; any other line that can't be parsed is an error.
+pub fn read_sessions(file_path: &Path) -> anyhow::Result<Vec<Session>> {
+    let (sessions, cut_off) = read_history_file(file_path)?;
+    if let Some(CutOffLine { error, .. }) = cut_off {
+        eprintln!(
+            "warning: the last line of tomo's history file is cut off, skipping it: {error:#}"
+        );
+    }
+
+    Ok(sessions)
+}
+
+/// Truncates the history log before its last line if that line can't be parsed, so that
+/// [`read_sessions`] only warns about it once; callers are expected to hold the history log's
+/// lock. Returns whether there was such a line.
+pub fn drop_cut_off_line(file_path: &Path) -> anyhow::Result<bool> {
+    let Some(CutOffLine { offset, .. }) = read_history_file(file_path)?.1 else {
+        return Ok(false);
+    };
+
+    OpenOptions::new()
+        .write(true)
+        .open(file_path)
+        .and_then(|file| file.set_len(offset as u64).and_then(|_| file.sync_data()))
+        .context("couldn't write to tomo's history file")?;
+
+    Ok(true)
+}
+
+/// Whether the history log ends with a newline; a missing or empty one counts as doing so. Lines
+/// cut off while being appended don't.
+pub fn ends_with_newline(file_path: &Path) -> anyhow::Result<bool> {
+    let mut file = match File::open(file_path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(true),
+        Err(e) => return Err(e).context("couldn't read from tomo's history file"),
+    };
+
+    let mut last = [b'\n'];
+    if file.metadata()?.len() > 0 {
+        file.seek(SeekFrom::End(-1))
+            .and_then(|_| file.read_exact(&mut last))
+            .context("couldn't read from tomo's history file")?;
+    }
+
+    Ok(last[0] == b'\n')
+}
+
+/// A last line of the history log that couldn't be parsed.
+struct CutOffLine {
+    /// Where the line starts, in bytes
+    offset: usize,
+    error: anyhow::Error,
+}
+
+/// Parses the history log, returning the sessions in it along with a last line that couldn't be
+/// parsed, if any.
+fn read_history_file(file_path: &Path) -> anyhow::Result<(Vec<Session>, Option<CutOffLine>)> {
+    // read as bytes, since a line can be cut off in the middle of a character
+    let contents = match fs::read(file_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok((vec![], None)),
+        Err(e) => return Err(e).context("couldn't read from tomo's history file"),
+    };
+
+    let mut offset = 0;
+    let mut lines = vec![];
+    for (i, line) in contents.split_inclusive(|b| *b == b'\n').enumerate() {
+        if !line.trim_ascii().is_empty() {
+            lines.push((i + 1, offset, line));
+        }
+        offset += line.len();
+    }
+
+    let mut sessions = Vec::with_capacity(lines.len());
+    let num_lines = lines.len();
+    for (n, (line_number, offset, line)) in lines.into_iter().enumerate() {
+        match serde_json::from_slice(line.trim_ascii()) {
+            Ok(session) => sessions.push(session),
+            Err(e) if n + 1 == num_lines => {
+                let error =
+                    anyhow::Error::from(e).context(format!("couldn't parse line {line_number}"));
+                return Ok((sessions, Some(CutOffLine { offset, error })));
+            }
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("couldn't parse line {line_number} of tomo's history file")
+                });
+            }
+        }
+    }
+
+    Ok((sessions, None))
+}
+
+/// Checks that a task is fit for the history log: not blank, and on a single line.
+pub fn validate_task(task: Option<&str>) -> anyhow::Result<()> {
+    match task {
+        Some(t) if t.trim().is_empty() => Err(anyhow::anyhow!("task cannot be empty")),
+        Some(t) if t.contains(['\n', '\r']) => {
+            Err(anyhow::anyhow!("task cannot span multiple lines"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that tags are fit for the history log (and for CSV exports, which join them with ',').
+pub fn validate_tags(tags: &[String]) -> anyhow::Result<()> {
+    for tag in tags {
+        if tag.is_empty() || tag.contains(|c: char| c.is_whitespace() || c == ',') {
+            return Err(anyhow::anyhow!(
+                "tags need to be non-empty, and cannot contain whitespace or ','"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// A bound of a time range, given either as an exact time (in RFC 3339 format) or as a date
+/// (YYYY-MM-DD, in local time).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeBound {
+    /// An exact time
+    Instant(DateTime<Utc>),
+    /// A whole (local) day
+    Date(NaiveDate),
+}
+
+impl FromStr for TimeBound {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return Ok(Self::Date(date));
+        }
+
+        DateTime::parse_from_rfc3339(s)
+            .map(|t| Self::Instant(t.to_utc()))
+            .map_err(|_| String::from("expected a date (YYYY-MM-DD) or an RFC 3339 timestamp"))
+    }
+}
+
+impl TimeBound {
+    /// The first instant included by this bound when used as the start of a range.
+    pub fn start(&self) -> DateTime<Utc> {
+        match self {
+            Self::Instant(t) => *t,
+            Self::Date(d) => local_midnight(*d),
+        }
+    }
+
+    /// The first instant excluded by this bound when used as the end of a range; dates are
+    /// inclusive.
+    pub fn end(&self) -> DateTime<Utc> {
+        match self {
+            Self::Instant(t) => *t,
+            Self::Date(d) => local_midnight(d.succ_opt().unwrap_or(*d)),
+        }
+    }
+}
+
+fn local_midnight(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    midnight
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|t| t.to_utc())
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+/// Which sessions to read from the history log; the default query matches all of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionQuery {
+    /// Only sessions that started at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only sessions that started before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Only sessions of this kind
+    pub kind: Option<SessionKind>,
+    /// Only sessions of these timers (or of all timers, if empty)
+    pub timers: Vec<String>,
+}
+
+impl SessionQuery {
+    /// Matches sessions that started within `[since, until)`.
+    pub fn between(since: Option<&TimeBound>, until: Option<&TimeBound>) -> Self {
+        Self {
+            since: since.map(TimeBound::start),
+            until: until.map(TimeBound::end),
+            ..Self::default()
+        }
+    }
+
+    /// Whether a session matches every part of the query.
+    pub fn matches(&self, session: &Session) -> bool {
+        self.since.is_none_or(|t| session.start >= t)
+            && self.until.is_none_or(|t| session.start < t)
+            && self.kind.is_none_or(|k| session.kind == k)
+            && (self.timers.is_empty() || self.timers.contains(&session.timer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn time(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts)
+            .expect("timestamp should've been parsed")
+            .to_utc()
+    }
+
+    fn session(timer: &str, start: &str, end: &str) -> Session {
+        Session {
+            timer: timer.to_string(),
+            kind: SessionKind::Focus,
+            start: time(start),
+            end: time(end),
+            planned_mins: Some(25),
+            task: None,
+            tags: vec![],
+        }
+    }
+
+    #[test]
+    fn appended_sessions_can_be_read_back() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let path = dir.path().join(".tomo.history");
+        let sessions = vec![
+            session("default", "2025-01-01T10:00:00Z", "2025-01-01T10:25:00Z"),
+            Session {
+                kind: SessionKind::Break,
+                planned_mins: None,
+                task: Some(String::from("write docs")),
+                tags: vec![String::from("docs")],
+                ..session("pairing", "2025-01-01T10:25:00Z", "2025-01-01T10:30:00Z")
+            },
+        ];
+
+        // WHEN
+        for s in &sessions {
+            append_session(&path, s).expect("session should've been appended");
+        }
+        let got = read_sessions(&path).expect("sessions should've been read");
+
+        // THEN
+        assert_eq!(got, sessions);
+    }
+
+    #[test]
+    fn a_cut_off_last_line_is_skipped_and_dropped_before_appending() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let path = dir.path().join(".tomo.history");
+        let first = session("default", "2025-01-01T10:00:00Z", "2025-01-01T10:25:00Z");
+        let second = session("default", "2025-01-01T11:00:00Z", "2025-01-01T11:25:00Z");
+        append_session(&path, &first).expect("session should've been appended");
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("history file should've been opened");
+        file.write_all(br#"{"timer":"default","kind":"fo"#)
+            .expect("history file should've been written");
+
+        // WHEN
+        let got_before = read_sessions(&path).expect("sessions should've been read");
+        append_session(&path, &second).expect("session should've been appended");
+        let got_after = read_sessions(&path).expect("sessions should've been read");
+
+        // THEN
+        assert_eq!(got_before, vec![first.clone()]);
+        assert_eq!(got_after, vec![first, second]);
+    }
+
+    #[test]
+    fn unparsable_lines_before_the_last_one_are_an_error() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let path = dir.path().join(".tomo.history");
+        fs::write(&path, "{\"timer\":\n").expect("history file should've been written");
+        append_session(
+            &path,
+            &session("default", "2025-01-01T10:00:00Z", "2025-01-01T10:25:00Z"),
+        )
+        .expect("session should've been appended");
+
+        // WHEN
+        let got = read_sessions(&path);
+
+        // THEN
+        assert!(got.is_err());
+    }
+
+    #[test]
+    fn missing_history_file_is_treated_as_empty() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+
+        // WHEN
+        let got =
+            read_sessions(&dir.path().join(".tomo.history")).expect("sessions should've been read");
+
+        // THEN
+        assert!(got.is_empty());
+    }
+
+    #[test]
+    fn sessions_shorter_than_planned_are_not_completed() {
+        // GIVEN
+        let complete = session("default", "2025-01-01T10:00:00Z", "2025-01-01T10:25:00Z");
+        let interrupted = session("default", "2025-01-01T10:00:00Z", "2025-01-01T10:24:59Z");
+
+        // WHEN
+        // THEN
+        assert!(complete.is_completed());
+        assert!(!interrupted.is_completed());
+    }
+
+    #[test]
+    fn querying_sessions_works() {
+        // GIVEN
+        let sessions = [
+            session("default", "2025-01-01T10:00:00Z", "2025-01-01T10:25:00Z"),
+            session("pairing", "2025-01-02T10:00:00Z", "2025-01-02T10:25:00Z"),
+            session("default", "2025-01-03T10:00:00Z", "2025-01-03T10:25:00Z"),
+        ];
+        let since = TimeBound::Instant(time("2025-01-02T00:00:00Z"));
+        let until = TimeBound::Instant(time("2025-01-03T10:00:00Z"));
+
+        let by_range = SessionQuery::between(Some(&since), Some(&until));
+        let by_timer = SessionQuery {
+            timers: vec![String::from("default")],
+            ..SessionQuery::default()
+        };
+
+        // WHEN
+        let got_range = sessions
+            .iter()
+            .filter(|s| by_range.matches(s))
+            .collect::<Vec<_>>();
+        let got_timer = sessions
+            .iter()
+            .filter(|s| by_timer.matches(s))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(got_range.len(), 1);
+        assert_eq!(got_range[0].timer, "pairing");
+        assert_eq!(got_timer.len(), 2);
+    }
+}
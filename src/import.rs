@@ -0,0 +1,415 @@
+use anyhow::Context;
+use chrono::{DateTime, Local, NaiveDateTime, TimeDelta, Utc};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::path::Path;
+use std::str::FromStr;
+use tomo::history::{Session, SessionKind, validate_tags, validate_task};
+use tomo::store::Store;
+use tomo::timer::validate_timer_name;
+use tomo::track::POMODORO_MINS;
+
+const LOCAL_TIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+];
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ImportFormat {
+    Csv,
+    Json,
+    Jsonl,
+}
+
+/// A field of a session that a CSV column can be mapped to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum SessionField {
+    Start,
+    End,
+    DurationMins,
+    Kind,
+    Timer,
+    PlannedMins,
+    Task,
+    Tags,
+}
+
+impl SessionField {
+    const ALL: [Self; 8] = [
+        Self::Start,
+        Self::End,
+        Self::DurationMins,
+        Self::Kind,
+        Self::Timer,
+        Self::PlannedMins,
+        Self::Task,
+        Self::Tags,
+    ];
+
+    /// The field's name, which is also the column it's read from when it's not mapped (these
+    /// match the columns of tomo's own CSV export).
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::End => "end",
+            Self::DurationMins => "duration_mins",
+            Self::Kind => "kind",
+            Self::Timer => "timer",
+            Self::PlannedMins => "planned_mins",
+            Self::Task => "task",
+            Self::Tags => "tags",
+        }
+    }
+}
+
+/// Maps a session field to a column of the CSV file being imported, eg. `start=Started At`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnMapping {
+    field: SessionField,
+    column: String,
+}
+
+impl FromStr for ColumnMapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = SessionField::ALL
+            .iter()
+            .map(|f| f.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let Some((field, column)) = s.split_once('=') else {
+            return Err(format!(
+                "expected FIELD=COLUMN, where FIELD is one of: {fields}"
+            ));
+        };
+
+        let Some(field) = SessionField::ALL
+            .into_iter()
+            .find(|f| f.as_str() == field.trim())
+        else {
+            return Err(format!("unknown field; expected one of: {fields}"));
+        };
+
+        if column.is_empty() {
+            return Err(String::from("column name cannot be empty"));
+        }
+
+        Ok(Self {
+            field,
+            column: column.to_string(),
+        })
+    }
+}
+
+/// A session as it appears in tomo's own JSON export; derived fields (like `actual_mins`) are
+/// ignored.
+#[derive(Debug, Deserialize)]
+struct ImportRecord {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    kind: SessionKind,
+    timer: Option<String>,
+    planned_mins: Option<i64>,
+    task: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Imports sessions into the history log, skipping ones that start at the same time as a session
+/// that's already recorded (or that appears earlier in the file being imported).
+pub fn import_history(
+    store: &dyn Store,
+    format: ImportFormat,
+    file_path: &Path,
+    columns: &[ColumnMapping],
+    tags_separator: &str,
+    default_timer: &str,
+) -> anyhow::Result<()> {
+    let sessions = match format {
+        ImportFormat::Csv => read_csv(file_path, columns, tags_separator, default_timer)?,
+        ImportFormat::Json => {
+            let file = File::open(file_path).context("couldn't open file to import")?;
+            let records: Vec<ImportRecord> =
+                serde_json::from_reader(file).context("couldn't parse JSON")?;
+            records
+                .into_iter()
+                .enumerate()
+                .map(|(i, r)| {
+                    to_session(r, default_timer)
+                        .with_context(|| format!("entry {} is invalid", i + 1))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?
+        }
+        ImportFormat::Jsonl => {
+            let contents = fs::read_to_string(file_path).context("couldn't read file to import")?;
+            contents
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| {
+                    serde_json::from_str(line)
+                        .context("couldn't parse JSON")
+                        .and_then(|r| to_session(r, default_timer))
+                        .with_context(|| format!("line {} is invalid", i + 1))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?
+        }
+    };
+
+    let num_sessions = sessions.len();
+    let mut sessions = Some(sessions);
+    let num_imported = store.append_new_sessions(&mut |recorded| {
+        let mut seen = recorded.iter().map(|s| s.start).collect::<HashSet<_>>();
+        let mut new_sessions = sessions
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| seen.insert(s.start))
+            .collect::<Vec<_>>();
+        new_sessions.sort_by_key(|s| s.start);
+
+        Ok(new_sessions)
+    })?;
+
+    println!(
+        "imported {num_imported} session(s), skipped {} duplicate(s)",
+        num_sessions - num_imported
+    );
+
+    Ok(())
+}
+
+fn to_session(record: ImportRecord, default_timer: &str) -> anyhow::Result<Session> {
+    let timer = record.timer.unwrap_or_else(|| default_timer.to_string());
+    validate_session(Session {
+        timer,
+        kind: record.kind,
+        start: record.start,
+        end: record.end,
+        planned_mins: record.planned_mins,
+        task: record.task,
+        tags: record.tags,
+    })
+}
+
+fn validate_session(mut session: Session) -> anyhow::Result<Session> {
+    validate_timer_name(&session.timer)?;
+    validate_task(session.task.as_deref())?;
+    validate_tags(&session.tags)?;
+
+    if session.end < session.start {
+        return Err(anyhow::anyhow!("session ends before it starts"));
+    }
+
+    if session.planned_mins.is_some_and(|p| p <= 0) {
+        return Err(anyhow::anyhow!("planned minutes need to be positive"));
+    }
+
+    // without a planned length, a focus session would count as a completed pomodoro however short
+    // it was
+    if session.kind == SessionKind::Focus && session.planned_mins.is_none() {
+        session.planned_mins = Some(POMODORO_MINS);
+    }
+
+    Ok(session)
+}
+
+fn read_csv(
+    file_path: &Path,
+    columns: &[ColumnMapping],
+    tags_separator: &str,
+    default_timer: &str,
+) -> anyhow::Result<Vec<Session>> {
+    let mut reader = csv::Reader::from_path(file_path).context("couldn't open file to import")?;
+    let headers = reader
+        .headers()
+        .context("couldn't read CSV header")?
+        .clone();
+
+    let mut indices = HashMap::new();
+    for field in SessionField::ALL {
+        let mapped = columns.iter().rev().find(|m| m.field == field);
+        let column = mapped.map_or(field.as_str(), |m| m.column.as_str());
+        match headers.iter().position(|h| h.trim() == column) {
+            Some(i) => {
+                indices.insert(field, i);
+            }
+            None if mapped.is_some() => {
+                return Err(anyhow::anyhow!(
+                    "column \"{column}\" (mapped to {}) is not in the CSV header",
+                    field.as_str()
+                ));
+            }
+            None => {}
+        }
+    }
+
+    if !indices.contains_key(&SessionField::Start) {
+        return Err(anyhow::anyhow!(
+            "CSV has no start column; map one with --column start=COLUMN"
+        ));
+    }
+    if !indices.contains_key(&SessionField::End)
+        && !indices.contains_key(&SessionField::DurationMins)
+    {
+        return Err(anyhow::anyhow!(
+            "CSV has neither an end nor a duration_mins column; map one with --column end=COLUMN or --column duration_mins=COLUMN"
+        ));
+    }
+
+    let mut sessions = vec![];
+    for (i, record) in reader.records().enumerate() {
+        // the header is on the first line
+        let line = i + 2;
+        let record = record.with_context(|| format!("couldn't read line {line} of the CSV"))?;
+        let value = |field: SessionField| {
+            indices
+                .get(&field)
+                .and_then(|i| record.get(*i))
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+
+        let session = parse_csv_row(value, tags_separator, default_timer)
+            .with_context(|| format!("line {line} of the CSV is invalid"))?;
+        sessions.push(session);
+    }
+
+    Ok(sessions)
+}
+
+fn parse_csv_row<'a>(
+    value: impl Fn(SessionField) -> Option<&'a str>,
+    tags_separator: &str,
+    default_timer: &str,
+) -> anyhow::Result<Session> {
+    let start = value(SessionField::Start)
+        .ok_or_else(|| anyhow::anyhow!("start is empty"))
+        .and_then(parse_time)?;
+
+    let end = match (value(SessionField::End), value(SessionField::DurationMins)) {
+        (Some(end), _) => parse_time(end)?,
+        (None, Some(mins)) => {
+            let mins = mins
+                .parse::<f64>()
+                .ok()
+                .filter(|m| m.is_finite() && *m >= 0.0)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("duration_mins needs to be a non-negative number")
+                })?;
+            // `as` saturates, so a huge duration ends up out of range rather than wrapping
+            TimeDelta::try_seconds((mins * 60.0).round() as i64)
+                .and_then(|duration| start.checked_add_signed(duration))
+                .ok_or_else(|| anyhow::anyhow!("duration_mins is out of range"))?
+        }
+        (None, None) => return Err(anyhow::anyhow!("both end and duration_mins are empty")),
+    };
+
+    let kind = match value(SessionField::Kind).map(str::to_lowercase).as_deref() {
+        None | Some("focus") | Some("pomodoro") => SessionKind::Focus,
+        Some("break") => SessionKind::Break,
+        Some(k) => {
+            return Err(anyhow::anyhow!(
+                "unknown kind \"{k}\"; expected focus, pomodoro, or break"
+            ));
+        }
+    };
+
+    let planned_mins = value(SessionField::PlannedMins)
+        .map(|p| {
+            p.parse::<i64>()
+                .map_err(|_| anyhow::anyhow!("planned_mins needs to be a whole number"))
+        })
+        .transpose()?;
+
+    let tags = value(SessionField::Tags)
+        .map(|t| {
+            t.split(tags_separator)
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    validate_session(Session {
+        timer: value(SessionField::Timer)
+            .unwrap_or(default_timer)
+            .to_string(),
+        kind,
+        start,
+        end,
+        planned_mins,
+        task: value(SessionField::Task).map(String::from),
+        tags,
+    })
+}
+
+/// Parses a timestamp in RFC 3339 format, or a date and time without an offset (which is taken
+/// to be in local time).
+fn parse_time(value: &str) -> anyhow::Result<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(value) {
+        return Ok(t.to_utc());
+    }
+
+    LOCAL_TIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(value, f).ok())
+        .and_then(|t| t.and_local_timezone(Local).earliest())
+        .map(|t| t.to_utc())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "couldn't parse time \"{value}\"; expected an RFC 3339 timestamp or YYYY-MM-DD HH:MM[:SS]"
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_mappings_are_parsed() {
+        // GIVEN
+        // WHEN
+        let got = ColumnMapping::from_str("start=Started At").expect("mapping should've parsed");
+
+        // THEN
+        assert_eq!(
+            got,
+            ColumnMapping {
+                field: SessionField::Start,
+                column: String::from("Started At"),
+            }
+        );
+    }
+
+    #[test]
+    fn column_mappings_for_unknown_fields_are_rejected() {
+        // GIVEN
+        // WHEN
+        let got = ["begin=Start", "start", "start="]
+            .iter()
+            .map(|m| ColumnMapping::from_str(m).is_err())
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert!(got.iter().all(|e| *e));
+    }
+
+    #[test]
+    fn times_with_offsets_are_parsed() {
+        // GIVEN
+        // WHEN
+        let got = parse_time("2025-01-06T10:00:00+01:00").expect("time should've parsed");
+
+        // THEN
+        assert_eq!(got.to_rfc3339(), "2025-01-06T09:00:00+00:00");
+    }
+}
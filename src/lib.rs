@@ -0,0 +1,57 @@
+//! tomo's core, for embedding it in other tools (eg. a status bar daemon, or an editor plugin).
+//!
+//! - [`state`] holds the timer state machine, and reads/writes tomo's data file
+//! - [`track`] moves timers between states (recording finished sessions in the history log), and
+//!   shows their progress
+//! - [`render`] turns timers' progress into output (a plain or styled progress bar, JSON, ...);
+//!   implement [`Renderer`] for a format of your own
+//! - [`timer`] resolves the data files of the default and named timers
+//! - [`history`] reads and writes the history log
+//! - [`store`] keeps timers' states and the history log, in files ([`FileStore`]) or in memory
+//!   ([`MemoryStore`]); with the `sqlite` feature, `sqlite` keeps the history log in a SQLite
+//!   database
+//!
+//! ```
+//! use tomo::{DisplayConfig, get_progress_bar};
+//!
+//! let config = DisplayConfig::default();
+//! assert_eq!(get_progress_bar(5 * 60, &config), " ▪▪▫▫▫▫▫▫▫▫ ");
+//! ```
+
+#![warn(missing_docs)]
+
+/// Defaults for the progress bar and messages shown in the status bar
+pub mod common;
+/// Settings for showing progress, and tomo's config file
+pub mod config;
+mod fs_utils;
+/// Counting completed pomodoros towards daily and weekly goals
+pub mod goals;
+/// The history log of finished pomodoros and breaks
+pub mod history;
+/// Locks that keep tomo processes from changing a data file (or the history log) at once
+pub mod lock;
+/// Turning timers' progress into output
+pub mod render;
+/// Keeping the history log in a SQLite database
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+/// The timer state machine, and tomo's data file
+pub mod state;
+/// Where timers' states and the history log are kept
+pub mod store;
+/// Where tomo gets the current time from
+pub mod time;
+/// The default and named timers, and their data files
+pub mod timer;
+/// Scoping timers to tmux sessions
+pub mod tmux;
+/// Moving timers between states, and showing their progress
+pub mod track;
+
+pub use config::DisplayConfig;
+pub use render::{Progress, Renderer, TimerProgress};
+pub use state::{Pomodoro, TimerState, read_state, write_state};
+pub use store::{FileStore, MemoryStore, Store};
+pub use timer::Timer;
+pub use track::{POMODORO_MINS, get_progress_bar};
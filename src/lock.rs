@@ -0,0 +1,123 @@
+use anyhow::Context;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// An advisory, exclusive lock on a data file, held until dropped.
+///
+/// The lock is taken on a separate `.lock` file next to the data file, since the data file itself
+/// gets replaced (rather than modified in place) on every write.
+#[derive(Debug)]
+pub struct DataFileLock {
+    _file: File,
+}
+
+impl DataFileLock {
+    /// Takes the lock on a data file (or the history log), waiting a couple of seconds at most
+    /// for another tomo process to release it.
+    pub fn acquire(data_file_path: &Path) -> anyhow::Result<Self> {
+        Self::acquire_with_timeout(data_file_path, LOCK_TIMEOUT)
+    }
+
+    fn acquire_with_timeout(data_file_path: &Path, timeout: Duration) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(lock_file_path(data_file_path))
+            .context("couldn't open tomo's lock file")?;
+
+        let started = Instant::now();
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(Self { _file: file }),
+                Err(TryLockError::WouldBlock) if started.elapsed() < timeout => {
+                    thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(anyhow::anyhow!(
+                        "timed out waiting for another tomo process to release the data file"
+                    ));
+                }
+                Err(TryLockError::Error(e)) => {
+                    return Err(e).context("couldn't lock tomo's data file");
+                }
+            }
+        }
+    }
+}
+
+/// Returns whether another process holds the lock on a data file, without waiting for it to be
+/// released (or creating the lock file).
+pub fn is_locked(data_file_path: &Path) -> anyhow::Result<bool> {
+    let file = match File::open(lock_file_path(data_file_path)) {
+        Ok(f) => f,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e).context("couldn't open tomo's lock file"),
+    };
+
+    match file.try_lock() {
+        Ok(()) => Ok(false),
+        Err(TryLockError::WouldBlock) => Ok(true),
+        Err(TryLockError::Error(e)) => Err(e).context("couldn't lock tomo's data file"),
+    }
+}
+
+/// Returns the lock file for a data file (eg. `.tomo.lock`).
+pub fn lock_file_path(data_file_path: &Path) -> PathBuf {
+    let mut file_name = data_file_path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    file_name.push(".lock");
+
+    data_file_path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn lock_is_exclusive_until_dropped() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let path = dir.path().join(".tomo");
+        let lock = DataFileLock::acquire(&path).expect("lock should've been acquired");
+
+        // WHEN
+        let got_while_held = DataFileLock::acquire_with_timeout(&path, Duration::from_millis(50));
+        drop(lock);
+        let got_after_drop = DataFileLock::acquire_with_timeout(&path, Duration::from_millis(50));
+
+        // THEN
+        assert!(got_while_held.is_err());
+        assert!(got_after_drop.is_ok());
+    }
+
+    #[test]
+    fn is_locked_reports_held_locks() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let path = dir.path().join(".tomo");
+        let before_first_lock = is_locked(&path).expect("lock should've been checked");
+        let lock = DataFileLock::acquire(&path).expect("lock should've been acquired");
+
+        // WHEN
+        let got_while_held = is_locked(&path).expect("lock should've been checked");
+        drop(lock);
+        let got_after_drop = is_locked(&path).expect("lock should've been checked");
+
+        // THEN
+        assert!(!before_first_lock);
+        assert!(got_while_held);
+        assert!(!got_after_drop);
+    }
+}
@@ -1,78 +1,256 @@
 mod args;
-mod common;
-mod config;
-mod track;
+mod clock;
+mod completions;
+mod doctor;
+mod export;
+mod import;
+mod man;
+mod report;
+mod stats;
+mod status;
+mod tui;
 use std::ops::RangeInclusive;
 
+use crate::clock::run_clock;
+use crate::doctor::run_doctor;
+use crate::export::export_history;
+use crate::import::import_history;
+use crate::report::{show_heatmap, show_label_report};
+use crate::stats::show_stats;
+use crate::status::show_status;
+use crate::tui::run_tui;
 use anyhow::Context;
 use args::{Action, Args};
-use chrono::{Duration, Utc};
-use clap::Parser;
-use config::DisplayConfig;
-use dirs::data_dir;
+use chrono::Duration;
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
+use completions::{COMPLETE_ENV_VAR, print_completions};
+use man::print_man_page;
 use std::fs;
 use std::path::PathBuf;
-use track::{show_progress, start_tracking, stop_tracking, take_break};
+use tomo::common::DEFAULT_IDLE_MSG;
+use tomo::config::{Config, DisplayConfig, default_config_file_path, read_config};
+use tomo::goals::{clear_goal_placeholders, fill_goal_placeholders};
+use tomo::history::{validate_tags, validate_task};
+use tomo::store::open_store;
+use tomo::time::TimeSource;
+use tomo::timer::{DEFAULT_TIMER_NAME, Timer, default_data_file_path, list_timers};
+use tomo::tmux;
+use tomo::track::{list_active_timers, show_progress, start_tracking, stop_tracking, take_break};
 
-const DATA_DIR: &str = "tomo";
-const DATA_FILE: &str = ".tomo";
 const ELAPSED_MINS_UPPER_LIMIT: u8 = 20;
 const NUM_BLOCKS_RANGE: RangeInclusive<u8> = 3..=100;
 
 fn main() -> anyhow::Result<()> {
+    CompleteEnv::with_factory(Args::command)
+        .var(COMPLETE_ENV_VAR)
+        .complete();
+
     let args = Args::parse();
 
+    match args.action {
+        Some(Action::Completions { shell }) => return print_completions(shell),
+        Some(Action::Man) => return print_man_page(),
+        _ => {}
+    }
+
     if !(NUM_BLOCKS_RANGE).contains(&args.num_blocks) {
         return Err(anyhow::anyhow!(
             "number of blocks needs to be between 3 and 100"
         ));
     }
 
-    let data_file_path = match args.data_file {
-        Some(f) => PathBuf::from(f),
-        None => {
-            let user_data_dir = data_dir().unwrap_or(PathBuf::from("."));
-            let data_dir = user_data_dir.join(PathBuf::from(DATA_DIR));
+    let data_file_is_default = args.data_file.is_none();
+    let data_file_path = args
+        .data_file
+        .map_or_else(default_data_file_path, PathBuf::from);
+    let config_file_path = args
+        .config_file
+        .map(PathBuf::from)
+        .unwrap_or_else(default_config_file_path);
 
-            if !data_dir.exists() {
-                fs::create_dir_all(&data_dir).context("could not create data directory")?;
-            }
+    let time = args.now.map_or(TimeSource::System, TimeSource::starting_at);
+    // one-off actions happen at exactly the given time
+    let now = args.now.unwrap_or_else(|| time.now());
+
+    // the doctor runs before anything is read or created, so that it can report what's wrong
+    if let Some(Action::Doctor) = args.action {
+        return run_doctor(&data_file_path, &config_file_path, now);
+    }
 
-            data_dir.join(PathBuf::from(DATA_FILE))
+    if data_file_is_default
+        && let Some(data_dir) = data_file_path.parent()
+        && !data_dir.exists()
+    {
+        fs::create_dir_all(data_dir).context("could not create data directory")?;
+    }
+
+    let store = open_store(&data_file_path)?;
+    let store = store.as_ref();
+
+    let config = match read_config(&config_file_path) {
+        Ok(c) => c,
+        // a typo in the config file shouldn't break the status line either; other commands (eg.
+        // stats and status) report it
+        Err(e) if args.action.is_none() => {
+            eprintln!("warning: ignoring tomo's config file: {e:#}");
+            Config::default()
         }
+        Err(e) => return Err(e),
     };
 
-    let now = Utc::now();
+    let mut display_config = DisplayConfig {
+        pending_block: args.pending_block,
+        complete_block: args.complete_block,
+        left_pad: args.left_pad,
+        right_pad: args.right_pad,
+        delimiter: args.delimiter,
+        num_blocks: args.num_blocks,
+        finished_msg: args.finished_msg,
+        break_msg: args.break_msg,
+        idle_msg: args
+            .idle_msg
+            .or_else(|| config.idle_msg.clone())
+            .unwrap_or_else(|| DEFAULT_IDLE_MSG.into()),
+        timer_separator: args.timer_separator,
+        accessible: args.accessible || config.accessible,
+    };
+    if let Err(e) = fill_goal_placeholders(&mut display_config, store, now, &config) {
+        // like a corrupt data file, a broken history log shouldn't break the status line
+        if args.action.is_some() {
+            return Err(e);
+        }
+        eprintln!("warning: couldn't count pomodoros for the goal placeholders: {e:#}");
+        clear_goal_placeholders(&mut display_config);
+    }
 
-    match args.action {
-        None => {
-            let config = DisplayConfig {
-                pending_block: args.pending_block,
-                complete_block: args.complete_block,
-                left_pad: args.left_pad,
-                right_pad: args.right_pad,
-                delimiter: args.delimiter,
-                num_blocks: args.num_blocks,
-                finished_msg: args.finished_msg,
-                break_msg: args.break_msg,
+    match &args.action {
+        Some(Action::List) => {
+            let timers = list_timers(&data_file_path)?;
+            return list_active_timers(store, &timers, now, &display_config, args.strict);
+        }
+        Some(Action::Report {
+            by: Some(by),
+            period,
+            sort,
+            format,
+            ..
+        }) => {
+            return show_label_report(store, now, *by, *period, *sort, *format, &args.timers);
+        }
+        Some(Action::Report { weeks, color, .. }) => {
+            return show_heatmap(store, now, *weeks, *color);
+        }
+        #[cfg(feature = "sqlite")]
+        Some(Action::Migrate) => {
+            return tomo::sqlite::migrate_history(&data_file_path);
+        }
+        Some(Action::Stats) => {
+            return show_stats(store, now, &config);
+        }
+        Some(Action::Export {
+            format,
+            since,
+            until,
+        }) => {
+            return export_history(store, *format, since.as_ref(), until.as_ref(), &args.timers);
+        }
+        Some(Action::Import {
+            from,
+            file,
+            columns,
+            tags_separator,
+        }) => {
+            let default_timer = match args.timers.as_slice() {
+                [] => DEFAULT_TIMER_NAME,
+                [t] => t.as_str(),
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "only one timer can be specified for this command"
+                    ));
+                }
             };
+            // validates the timer's name
+            Timer::new(&data_file_path, Some(default_timer))?;
 
-            show_progress(&data_file_path, now, &config)
+            return import_history(store, *from, file, columns, tags_separator, default_timer);
         }
-        Some(Action::Start { elapsed_mins }) => {
+        _ => {}
+    }
+
+    let timer_names = if args.timers.is_empty() && args.tmux_scope {
+        tmux::current_session()
+            .map(|s| vec![tmux::session_timer_name(&s)])
+            .unwrap_or_default()
+    } else {
+        args.timers
+    };
+
+    let timers = match timer_names.as_slice() {
+        [] => vec![Timer::new(&data_file_path, None)?],
+        names => names
+            .iter()
+            .map(|n| Timer::new(&data_file_path, Some(n)))
+            .collect::<anyhow::Result<Vec<_>>>()?,
+    };
+
+    let Some(action) = args.action else {
+        return show_progress(
+            store,
+            &timers,
+            now,
+            &display_config,
+            args.output.renderer().as_ref(),
+            args.strict,
+        );
+    };
+
+    let timer = match timers.as_slice() {
+        [t] => t,
+        _ => {
+            return Err(anyhow::anyhow!(
+                "only one timer can be specified for this command"
+            ));
+        }
+    };
+    match action {
+        Action::Start {
+            elapsed_mins,
+            task,
+            tags,
+        } => {
             if elapsed_mins > ELAPSED_MINS_UPPER_LIMIT {
                 return Err(anyhow::anyhow!(
                     "elapsed mins cannot be greater than {}",
                     ELAPSED_MINS_UPPER_LIMIT
                 ));
             }
+            validate_task(task.as_deref())?;
+            validate_tags(&tags)?;
             start_tracking(
-                &data_file_path,
+                store,
+                timer,
                 now - Duration::minutes(elapsed_mins as i64),
+                now,
+                task,
+                tags,
             )
         }
-        Some(Action::Stop) => stop_tracking(&data_file_path),
-        Some(Action::Break) => take_break(&data_file_path),
+        Action::Stop => stop_tracking(store, timer, now),
+        Action::Break => take_break(store, timer, now),
+        Action::Status => show_status(store, timer, now, &config, args.strict),
+        Action::Tui => run_tui(store, timer, &display_config, &config, &time),
+        Action::Clock => run_clock(store, timer, &display_config, &time),
+        Action::List
+        | Action::Completions { .. }
+        | Action::Man
+        | Action::Export { .. }
+        | Action::Import { .. }
+        | Action::Stats
+        | Action::Report { .. }
+        | Action::Doctor => Ok(()),
+        #[cfg(feature = "sqlite")]
+        Action::Migrate => Ok(()),
     }?;
 
     Ok(())
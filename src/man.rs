@@ -0,0 +1,154 @@
+use crate::args::Args;
+use clap::CommandFactory;
+use clap_mangen::Man;
+use roff::{Roff, bold, italic, roman};
+use std::io::Write;
+
+/// Prints tomo's man page, rendered from its CLI definition, with extra sections describing the
+/// data file and the environment variables tomo reads.
+pub fn print_man_page() -> anyhow::Result<()> {
+    let man = Man::new(Args::command()).title("TOMO").source("tomo");
+
+    let mut stdout = std::io::stdout().lock();
+    man.render(&mut stdout)?;
+    extra_sections().to_writer(&mut stdout)?;
+    stdout.flush()?;
+
+    Ok(())
+}
+
+fn extra_sections() -> Roff {
+    let mut roff = Roff::default();
+
+    roff.control("SH", ["DATA FILE"]);
+    roff.text([roman(
+        "tomo keeps the state of a timer in a plain text data file, which holds one of the following:",
+    )]);
+    roff.control("TP", []);
+    roff.text([italic("TIMESTAMP")]);
+    roff.text([roman(
+        "A pomodoro is running, and was started at TIMESTAMP (in RFC 3339 format).",
+    )]);
+    roff.control("TP", []);
+    roff.text([bold("break"), roman(" ["), italic("TIMESTAMP"), roman("]")]);
+    roff.text([roman(
+        "A break is running, and was started at TIMESTAMP (if known).",
+    )]);
+    roff.control("TP", []);
+    roff.text([bold("stop"), roman(" ["), italic("TIMESTAMP"), roman("]")]);
+    roff.text([roman(
+        "The timer is stopped, and was stopped at TIMESTAMP (if known). A missing data file is \
+         treated the same way, unless --strict is passed.",
+    )]);
+    roff.control("PP", []);
+    roff.text([roman(
+        "While a pomodoro is running, further lines can hold the task it's for (\"task: LABEL\") \
+         and its tags (\"tags: TAG1,TAG2\").",
+    )]);
+    roff.control("PP", []);
+    roff.text([roman(
+        "The data file is replaced atomically on every write. Concurrent invocations of tomo are \
+         serialised via an advisory lock on a \".lock\" file next to the data file.",
+    )]);
+
+    roff.control("SH", ["CONFIG FILE"]);
+    roff.text([roman(
+        "tomo reads optional settings from a TOML config file. A missing config file is the same \
+         as an empty one. The following settings are supported:",
+    )]);
+    roff.control("TP", []);
+    roff.text([bold("daily_goal"), roman(" = "), italic("NUM")]);
+    roff.text([roman("Number of pomodoros to aim for each day.")]);
+    roff.control("TP", []);
+    roff.text([bold("weekly_goal"), roman(" = "), italic("NUM")]);
+    roff.text([roman(
+        "Number of pomodoros to aim for each week (weeks start on Monday).",
+    )]);
+    roff.control("TP", []);
+    roff.text([bold("idle_msg"), roman(" = "), italic("STRING")]);
+    roff.text([roman(
+        "Message to show when no timer is running, unless --idle-msg is passed.",
+    )]);
+    roff.control("TP", []);
+    roff.text([bold("accessible"), roman(" = "), italic("BOOL")]);
+    roff.text([roman(
+        "Describe progress in words (eg. \"focus, 14 minutes left\") rather than with a progress \
+         bar, in every output format; the same as passing --accessible.",
+    )]);
+    roff.control("PP", []);
+    roff.text([roman(
+        "In the messages and pads shown in the status bar, \"{today}\" and \"{week}\" are \
+         replaced with the number of pomodoros completed today and this week, and \"{goal}\" \
+         and \"{weekly_goal}\" with the goals for them (or \"-\" if not set). \"tomo stats\" \
+         shows how often the goals were met.",
+    )]);
+
+    roff.control("SH", ["FILES"]);
+    roff.control("TP", []);
+    roff.text([italic("<YOUR_DATA_DIR>/tomo/.tomo")]);
+    roff.text([roman("Data file for the default timer.")]);
+    roff.control("TP", []);
+    roff.text([italic("<YOUR_DATA_DIR>/tomo/.tomo-NAME")]);
+    roff.text([roman(
+        "Data file for the timer NAME. Named timers are always kept next to the default timer's \
+         data file, including when --data-file is passed.",
+    )]);
+    roff.control("TP", []);
+    roff.text([italic("<YOUR_DATA_DIR>/tomo/.tomo.history")]);
+    roff.text([roman(
+        "History log, shared by all timers. Every finished pomodoro and break is appended to it \
+         as a JSON object on its own line.",
+    )]);
+    roff.control("TP", []);
+    roff.text([italic("<YOUR_DATA_DIR>/tomo/.tomo.db")]);
+    roff.text([roman(
+        "SQLite database that takes over from the history log once \"tomo migrate\" has created \
+         it (only in builds with the sqlite feature).",
+    )]);
+    roff.control("TP", []);
+    roff.text([italic("<YOUR_DATA_DIR>/tomo/.tomo.lock")]);
+    roff.text([roman(
+        "Lock file that keeps tomo processes from changing a timer's data file at the same time. \
+         Each data file has one next to it (eg. .tomo-NAME.lock), and appends to the history log \
+         are guarded by .tomo.history.lock.",
+    )]);
+    roff.control("TP", []);
+    roff.text([italic("<YOUR_CONFIG_DIR>/tomo/config.toml")]);
+    roff.text([roman("Config file.")]);
+
+    roff.control("SH", ["ENVIRONMENT"]);
+    roff.control("TP", []);
+    roff.text([bold("TOMO_DATA_FILE")]);
+    roff.text([roman("Data file to use, same as --data-file.")]);
+    roff.control("TP", []);
+    roff.text([bold("TOMO_CONFIG_FILE")]);
+    roff.text([roman("Config file to use, same as --config-file.")]);
+    roff.control("TP", []);
+    roff.text([bold("TOMO_TMUX_SCOPE")]);
+    roff.text([roman(
+        "If set to true, keep a separate timer for each tmux session, same as --tmux-scope.",
+    )]);
+    roff.control("TP", []);
+    roff.text([bold("TOMO_ACCESSIBLE")]);
+    roff.text([roman(
+        "If set to true, describe progress in words rather than with a progress bar, same as \
+         --accessible.",
+    )]);
+    roff.control("TP", []);
+    roff.text([bold("TMUX")]);
+    roff.text([roman(
+        "Used to detect whether tomo is running inside tmux when scoping timers to tmux sessions.",
+    )]);
+    roff.control("TP", []);
+    roff.text([bold("NO_COLOR")]);
+    roff.text([roman(
+        "If set, \"tomo report\" doesn't colour its output unless --color always is passed.",
+    )]);
+    roff.control("TP", []);
+    roff.text([bold("COMPLETE")]);
+    roff.text([roman(
+        "Set by the scripts printed by \"tomo completions\" when asking tomo for completions.",
+    )]);
+
+    roff
+}
@@ -0,0 +1,586 @@
+use crate::config::DisplayConfig;
+use crate::track::{
+    CLOCK_SKEW_TOLERANCE_SECS, POMODORO_MINS, format_clock, get_idle_msg, progress_bar_parts,
+};
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// A timer's progress at a point in time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Progress {
+    /// No pomodoro is running
+    Stopped {
+        /// How long ago the timer was stopped, if known
+        idle_seconds: Option<i64>,
+    },
+    /// A break is running
+    OnBreak,
+    /// A pomodoro is running (or is done, but hasn't been stopped yet)
+    Running {
+        /// Time since the pomodoro started; negative if it starts in the future
+        elapsed_seconds: i64,
+        /// What's being worked on
+        task: Option<String>,
+    },
+}
+
+/// The progress of a timer, along with its name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerProgress {
+    /// Name of the timer
+    pub timer: String,
+    /// Where the timer is at
+    pub progress: Progress,
+}
+
+/// Turns the progress of timers into what tomo prints. Implement this to show tomo's progress in
+/// a format of your own.
+pub trait Renderer {
+    /// Returns the output for one or more timers (several timers are shown in one segment),
+    /// including any trailing newline. `None` means that nothing is printed.
+    fn render(&self, timers: &[TimerProgress], config: &DisplayConfig) -> Option<String>;
+}
+
+/// The output formats tomo ships with: a progress bar that's either `Plain`, or styled for `Tmux`
+/// or for terminals (`Ansi`); a `Json` object (or an array, for several timers); or a JSON object
+/// as `Waybar`'s custom modules expect it.
+// doc comments on the variants would become help text for --output, and change --help's layout
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum OutputFormat {
+    Plain,
+    Tmux,
+    Ansi,
+    Json,
+    Waybar,
+}
+
+impl OutputFormat {
+    /// Returns the renderer for this format.
+    pub fn renderer(self) -> Box<dyn Renderer> {
+        match self {
+            Self::Plain => Box::new(BarRenderer::new(Palette::PLAIN)),
+            Self::Tmux => Box::new(BarRenderer::new(Palette::TMUX)),
+            Self::Ansi => Box::new(BarRenderer::new(Palette::ANSI)),
+            Self::Json => Box::new(JsonRenderer),
+            Self::Waybar => Box::new(WaybarRenderer),
+        }
+    }
+}
+
+/// Styles for the parts of a progress bar; each styled part is followed by `reset`.
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    /// Style for the blocks of a pomodoro that are done
+    pub complete: &'static str,
+    /// Style for the blocks of a pomodoro that are still to go
+    pub pending: &'static str,
+    /// Style for the message shown once a pomodoro is done
+    pub finished: &'static str,
+    /// Style for the message shown during a break
+    pub on_break: &'static str,
+    /// Resets the style
+    pub reset: &'static str,
+}
+
+impl Palette {
+    /// No styling at all
+    pub const PLAIN: Self = Self {
+        complete: "",
+        pending: "",
+        finished: "",
+        on_break: "",
+        reset: "",
+    };
+    /// Styles for tmux's status line
+    pub const TMUX: Self = Self {
+        complete: "#[fg=green]",
+        pending: "#[fg=brightblack]",
+        finished: "#[fg=red]",
+        on_break: "#[fg=cyan]",
+        reset: "#[default]",
+    };
+    /// Styles for terminals
+    pub const ANSI: Self = Self {
+        complete: "\x1b[32m",
+        pending: "\x1b[90m",
+        finished: "\x1b[31m",
+        on_break: "\x1b[36m",
+        reset: "\x1b[0m",
+    };
+
+    fn paint(&self, text: &str, style: &str) -> String {
+        if text.is_empty() || style.is_empty() {
+            return text.to_string();
+        }
+
+        format!("{style}{text}{}", self.reset)
+    }
+}
+
+const ACCESSIBLE_TIMER_SEPARATOR: &str = "; ";
+
+/// Renders timers as progress bars (or, in the accessible mode, describes them in words), each
+/// prefixed by its timer's name when there are several of them. Stopped timers are left out; if
+/// all of them are stopped, the idle message is shown.
+pub struct BarRenderer {
+    palette: Palette,
+}
+
+impl BarRenderer {
+    /// Returns a renderer that styles the progress bar with `palette`.
+    pub fn new(palette: Palette) -> Self {
+        Self { palette }
+    }
+
+    fn segment(&self, progress: &Progress, config: &DisplayConfig) -> Option<String> {
+        let palette = &self.palette;
+        match progress {
+            Progress::Stopped { .. } => None,
+            _ if config.accessible => {
+                let style = match progress {
+                    Progress::OnBreak => palette.on_break,
+                    Progress::Running {
+                        elapsed_seconds, ..
+                    } if remaining_seconds(*elapsed_seconds) == 0 => palette.finished,
+                    _ => palette.complete,
+                };
+                Some(palette.paint(&describe_in_words(progress), style))
+            }
+            Progress::OnBreak => Some(palette.paint(&config.break_msg, palette.on_break)),
+            Progress::Running {
+                elapsed_seconds, ..
+            } => Some(match progress_bar_parts(*elapsed_seconds, config) {
+                Some((complete, pending)) => {
+                    palette.paint(&complete, palette.complete)
+                        + &palette.paint(&pending, palette.pending)
+                }
+                None => palette.paint(&config.finished_msg, palette.finished),
+            }),
+        }
+    }
+}
+
+impl Renderer for BarRenderer {
+    fn render(&self, timers: &[TimerProgress], config: &DisplayConfig) -> Option<String> {
+        let segments = timers
+            .iter()
+            .filter_map(|t| {
+                let segment = self.segment(&t.progress, config)?;
+                Some(match timers {
+                    [_] => segment,
+                    _ if config.accessible => format!("{}: {segment}", t.timer),
+                    _ => format!("{} {segment}", t.timer),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if segments.is_empty() {
+            let idle_seconds = timers
+                .iter()
+                .filter_map(|t| match t.progress {
+                    Progress::Stopped { idle_seconds } => idle_seconds,
+                    _ => None,
+                })
+                .min();
+            return get_idle_msg(idle_seconds, config).map(|msg| msg + "\n");
+        }
+
+        let separator = if config.accessible {
+            ACCESSIBLE_TIMER_SEPARATOR
+        } else {
+            &config.timer_separator
+        };
+        let output = format!(
+            "{}{}{}",
+            config.left_pad,
+            segments.join(separator),
+            config.right_pad
+        );
+
+        // a single timer's break message has never been followed by a newline
+        match timers {
+            [
+                TimerProgress {
+                    progress: Progress::OnBreak,
+                    ..
+                },
+            ] => Some(output),
+            _ => Some(output + "\n"),
+        }
+    }
+}
+
+const CLOCK_SKEW_WARNING: &str = "clock_skew";
+
+/// Renders each timer as a JSON object (in an array, when there are several of them). Timers
+/// whose state is in the future get a warning.
+pub struct JsonRenderer;
+
+#[derive(Debug, Serialize)]
+struct TimerStatus<'a> {
+    timer: &'a str,
+    state: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elapsed_seconds: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remaining_seconds: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    idle_seconds: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task: Option<&'a str>,
+    /// What the plain output shows for the timer
+    text: String,
+    /// Set when the timer's state is in the future, which usually means that a clock is off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skew_seconds: Option<i64>,
+}
+
+impl Renderer for JsonRenderer {
+    fn render(&self, timers: &[TimerProgress], config: &DisplayConfig) -> Option<String> {
+        let statuses = timers
+            .iter()
+            .map(|t| timer_status(t, config))
+            .collect::<Vec<_>>();
+
+        let json = match statuses.as_slice() {
+            [status] => serde_json::to_string(status),
+            _ => serde_json::to_string(&statuses),
+        }
+        .expect("timer statuses should be serializable");
+
+        Some(json + "\n")
+    }
+}
+
+fn timer_status<'a>(timer: &'a TimerProgress, config: &DisplayConfig) -> TimerStatus<'a> {
+    let mut status = TimerStatus {
+        timer: &timer.timer,
+        state: state_name(&timer.progress),
+        elapsed_seconds: None,
+        remaining_seconds: None,
+        idle_seconds: None,
+        task: None,
+        text: plain_text(std::slice::from_ref(timer), config),
+        warning: None,
+        skew_seconds: None,
+    };
+
+    if let Some(skew) = skew_seconds(&timer.progress) {
+        status.warning = Some(CLOCK_SKEW_WARNING);
+        status.skew_seconds = Some(skew);
+    }
+
+    match &timer.progress {
+        Progress::Stopped { idle_seconds } => status.idle_seconds = idle_seconds.map(|s| s.max(0)),
+        Progress::OnBreak => {}
+        Progress::Running {
+            elapsed_seconds,
+            task,
+        } => {
+            status.elapsed_seconds = Some((*elapsed_seconds).max(0));
+            status.remaining_seconds = Some(remaining_seconds(*elapsed_seconds));
+            status.task = task.as_deref();
+        }
+    }
+
+    status
+}
+
+/// Renders timers for a waybar custom module (with `"return-type": "json"`): the plain output as
+/// its text, each timer's status in the tooltip, and the state of the first active timer as its
+/// class.
+pub struct WaybarRenderer;
+
+#[derive(Debug, Serialize)]
+struct WaybarStatus {
+    text: String,
+    tooltip: String,
+    class: &'static str,
+    percentage: i64,
+}
+
+impl Renderer for WaybarRenderer {
+    fn render(&self, timers: &[TimerProgress], config: &DisplayConfig) -> Option<String> {
+        let active = timers
+            .iter()
+            .find(|t| !matches!(t.progress, Progress::Stopped { .. }));
+
+        let status = WaybarStatus {
+            text: plain_text(timers, config),
+            tooltip: timers
+                .iter()
+                .map(|t| format!("{}: {}", t.timer, describe(&t.progress, config)))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            class: active.map_or("stopped", |t| state_name(&t.progress)),
+            percentage: match active.map(|t| &t.progress) {
+                Some(Progress::Running {
+                    elapsed_seconds, ..
+                }) => (elapsed_seconds * 100 / (POMODORO_MINS * 60)).clamp(0, 100),
+                _ => 0,
+            },
+        };
+
+        let json = serde_json::to_string(&status).expect("waybar status should be serializable");
+
+        Some(json + "\n")
+    }
+}
+
+fn remaining_seconds(elapsed_seconds: i64) -> i64 {
+    (POMODORO_MINS * 60 - elapsed_seconds.max(0)).max(0)
+}
+
+/// Returns how far in the future a timer's state is, if that's more than can be put down to
+/// timing noise.
+fn skew_seconds(progress: &Progress) -> Option<i64> {
+    let seconds_since = match progress {
+        Progress::Stopped { idle_seconds } => (*idle_seconds)?,
+        Progress::OnBreak => return None,
+        Progress::Running {
+            elapsed_seconds, ..
+        } => *elapsed_seconds,
+    };
+
+    (seconds_since < -CLOCK_SKEW_TOLERANCE_SECS).then_some(-seconds_since)
+}
+
+fn state_name(progress: &Progress) -> &'static str {
+    match progress {
+        Progress::Stopped { .. } => "stopped",
+        Progress::OnBreak => "break",
+        Progress::Running {
+            elapsed_seconds, ..
+        } if remaining_seconds(*elapsed_seconds) == 0 => "finished",
+        Progress::Running { .. } => "running",
+    }
+}
+
+fn describe(progress: &Progress, config: &DisplayConfig) -> String {
+    match progress {
+        Progress::Stopped { .. } => String::from("stopped"),
+        Progress::OnBreak => String::from("on a break"),
+        Progress::Running {
+            elapsed_seconds,
+            task,
+        } => {
+            let remaining = remaining_seconds(*elapsed_seconds);
+            let status = if config.accessible {
+                describe_in_words(progress)
+            } else if remaining > 0 {
+                format!("{} left", format_clock(remaining))
+            } else {
+                String::from("done")
+            };
+            match task {
+                Some(task) => format!("{status} ({task})"),
+                None => status,
+            }
+        }
+    }
+}
+
+/// Describes a timer's progress in words, eg. "focus, 14 minutes left", without any of the
+/// glyphs of the progress bar.
+pub(crate) fn describe_in_words(progress: &Progress) -> String {
+    match progress {
+        Progress::Stopped { .. } => String::from("stopped"),
+        Progress::OnBreak => String::from("break"),
+        Progress::Running {
+            elapsed_seconds, ..
+        } => match remaining_seconds(*elapsed_seconds) {
+            0 => String::from("focus, done"),
+            // rounded up, so that the last minute is "1 minute left" rather than "0 minutes left"
+            remaining => match (remaining + 59) / 60 {
+                1 => String::from("focus, 1 minute left"),
+                mins => format!("focus, {mins} minutes left"),
+            },
+        },
+    }
+}
+
+/// What the plain output shows for some timers, without a trailing newline.
+fn plain_text(timers: &[TimerProgress], config: &DisplayConfig) -> String {
+    BarRenderer::new(Palette::PLAIN)
+        .render(timers, config)
+        .map(|output| output.trim_end_matches('\n').to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn running(timer: &str, elapsed_mins: i64, task: Option<&str>) -> TimerProgress {
+        TimerProgress {
+            timer: timer.to_string(),
+            progress: Progress::Running {
+                elapsed_seconds: elapsed_mins * 60,
+                task: task.map(String::from),
+            },
+        }
+    }
+
+    fn stopped(timer: &str, idle_mins: i64) -> TimerProgress {
+        TimerProgress {
+            timer: timer.to_string(),
+            progress: Progress::Stopped {
+                idle_seconds: Some(idle_mins * 60),
+            },
+        }
+    }
+
+    #[test]
+    fn bar_renderer_combines_several_timers() {
+        // GIVEN
+        let timers = [
+            running("default", 10, None),
+            stopped("pairing", 5),
+            TimerProgress {
+                timer: String::from("review"),
+                progress: Progress::OnBreak,
+            },
+        ];
+
+        // WHEN
+        let got = BarRenderer::new(Palette::PLAIN).render(&timers, &DisplayConfig::default());
+
+        // THEN
+        assert_eq!(got.as_deref(), Some(" default ▪▪▪▪▫▫▫▫▫▫ | review \\o/ \n"));
+    }
+
+    #[test]
+    fn bar_renderer_shows_the_shortest_idle_time_when_all_timers_are_stopped() {
+        // GIVEN
+        let config = DisplayConfig {
+            idle_msg: String::from("idle for {idle}"),
+            ..DisplayConfig::default()
+        };
+        let timers = [stopped("default", 50), stopped("pairing", 5)];
+
+        // WHEN
+        let got = BarRenderer::new(Palette::PLAIN).render(&timers, &config);
+
+        // THEN
+        assert_eq!(got.as_deref(), Some(" idle for 5m \n"));
+    }
+
+    #[test]
+    fn tmux_renderer_styles_the_bar() {
+        // GIVEN
+        let timers = [running("default", 10, None)];
+
+        // WHEN
+        let got = BarRenderer::new(Palette::TMUX).render(&timers, &DisplayConfig::default());
+
+        // THEN
+        assert_eq!(
+            got.as_deref(),
+            Some(" #[fg=green]▪▪▪▪#[default]#[fg=brightblack]▫▫▫▫▫▫#[default] \n")
+        );
+    }
+
+    #[test]
+    fn ansi_renderer_styles_the_finished_msg() {
+        // GIVEN
+        let timers = [running("default", 30, None)];
+
+        // WHEN
+        let got = BarRenderer::new(Palette::ANSI).render(&timers, &DisplayConfig::default());
+
+        // THEN
+        assert_eq!(got.as_deref(), Some(" \x1b[31mdone\x1b[0m \n"));
+    }
+
+    #[test]
+    fn bar_renderer_describes_timers_in_words_in_the_accessible_mode() {
+        // GIVEN
+        let config = DisplayConfig {
+            accessible: true,
+            ..DisplayConfig::default()
+        };
+        let timers = [
+            running("default", 10, None),
+            running("pairing", 25, None),
+            TimerProgress {
+                timer: String::from("review"),
+                progress: Progress::OnBreak,
+            },
+        ];
+
+        // WHEN
+        let got = BarRenderer::new(Palette::PLAIN).render(&timers, &config);
+
+        // THEN
+        assert_eq!(
+            got.as_deref(),
+            Some(" default: focus, 15 minutes left; pairing: focus, done; review: break \n")
+        );
+    }
+
+    #[test]
+    fn describe_in_words_rounds_up_to_the_minute() {
+        // GIVEN
+        let progress = Progress::Running {
+            elapsed_seconds: 24 * 60 + 30,
+            task: None,
+        };
+
+        // WHEN
+        let got = describe_in_words(&progress);
+
+        // THEN
+        assert_eq!(got, "focus, 1 minute left");
+    }
+
+    #[test]
+    fn json_renderer_works() {
+        // GIVEN
+        let timers = [running("default", 10, Some("JIRA-123"))];
+
+        // WHEN
+        let got = JsonRenderer.render(&timers, &DisplayConfig::default());
+
+        // THEN
+        insta::assert_snapshot!(got.unwrap_or_default(), @r#"{"timer":"default","state":"running","elapsed_seconds":600,"remaining_seconds":900,"task":"JIRA-123","text":" ▪▪▪▪▫▫▫▫▫▫ "}"#);
+    }
+
+    #[test]
+    fn json_renderer_shows_several_timers_as_an_array() {
+        // GIVEN
+        let timers = [running("default", 25, None), stopped("pairing", 5)];
+
+        // WHEN
+        let got = JsonRenderer.render(&timers, &DisplayConfig::default());
+
+        // THEN
+        insta::assert_snapshot!(got.unwrap_or_default(), @r#"[{"timer":"default","state":"finished","elapsed_seconds":1500,"remaining_seconds":0,"text":" done "},{"timer":"pairing","state":"stopped","idle_seconds":300,"text":""}]"#);
+    }
+
+    #[test]
+    fn json_renderer_warns_about_start_times_in_the_future() {
+        // GIVEN
+        let timers = [running("default", -90, None)];
+
+        // WHEN
+        let got = JsonRenderer.render(&timers, &DisplayConfig::default());
+
+        // THEN
+        insta::assert_snapshot!(got.unwrap_or_default(), @r#"{"timer":"default","state":"running","elapsed_seconds":0,"remaining_seconds":1500,"text":" ▫▫▫▫▫▫▫▫▫▫ ","warning":"clock_skew","skew_seconds":5400}"#);
+    }
+
+    #[test]
+    fn waybar_renderer_works() {
+        // GIVEN
+        let timers = [stopped("default", 5), running("pairing", 10, Some("docs"))];
+
+        // WHEN
+        let got = WaybarRenderer.render(&timers, &DisplayConfig::default());
+
+        // THEN
+        insta::assert_snapshot!(got.unwrap_or_default(), @r#"{"text":" pairing ▪▪▪▪▫▫▫▫▫▫ ","tooltip":"default: stopped\npairing: 15:00 left (docs)","class":"running","percentage":40}"#);
+    }
+}
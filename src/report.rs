@@ -0,0 +1,535 @@
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Timelike, Utc};
+use clap::ValueEnum;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::io::{IsTerminal, Write};
+use tomo::goals::{local_date, pomodoros_per_day, week_start};
+use tomo::history::{Session, SessionKind, SessionQuery, TimeBound};
+use tomo::store::Store;
+
+const HEATMAP_LEVELS: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+// 256-colour greens, from (almost) nothing to a lot
+const HEATMAP_COLOURS: [&str; 5] = [
+    "\x1b[38;5;240m",
+    "\x1b[38;5;22m",
+    "\x1b[38;5;28m",
+    "\x1b[38;5;34m",
+    "\x1b[38;5;40m",
+];
+const ANSI_RESET: &str = "\x1b[0m";
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const ROW_LABEL_WIDTH: usize = 4;
+const MONTH_LABEL_WIDTH: usize = 3;
+const HISTOGRAM_WIDTH: usize = 40;
+const NO_TASK_LABEL: &str = "(no task)";
+const NO_TAG_LABEL: &str = "(no tag)";
+const TOTAL_LABEL: &str = "total";
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Whether to colour output; in auto mode, that's when stdout is a terminal and NO_COLOR
+    /// isn't set.
+    fn enabled(&self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => std::io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ReportBy {
+    Task,
+    Tag,
+}
+
+impl ReportBy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Task => "task",
+            Self::Tag => "tag",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ReportPeriod {
+    Day,
+    Week,
+    Month,
+    All,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ReportSort {
+    Minutes,
+    Pomodoros,
+    Label,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum ReportFormat {
+    Table,
+    Csv,
+    Markdown,
+}
+
+/// Focus time spent on a task or tag.
+#[derive(Debug, Clone, PartialEq)]
+struct ReportRow {
+    label: String,
+    /// Completed pomodoros
+    pomodoros: u32,
+    /// Minutes of focus, including time spent in interrupted pomodoros
+    minutes: i64,
+}
+
+/// Shows how many pomodoros and minutes of focus went into each task or tag during the current
+/// day, week (starting on Monday), or month, or overall. A pomodoro with several tags counts
+/// towards each of them, but only once towards the total.
+pub fn show_label_report(
+    store: &dyn Store,
+    now: DateTime<Utc>,
+    by: ReportBy,
+    period: ReportPeriod,
+    sort: ReportSort,
+    format: ReportFormat,
+    timers: &[String],
+) -> anyhow::Result<()> {
+    let today = local_date(&now);
+    let since = match period {
+        ReportPeriod::Day => Some(today),
+        ReportPeriod::Week => Some(week_start(today)),
+        ReportPeriod::Month => today.with_day(1),
+        ReportPeriod::All => None,
+    }
+    .map(TimeBound::Date);
+
+    let sessions = store.query_sessions(&SessionQuery {
+        kind: Some(SessionKind::Focus),
+        timers: timers.to_vec(),
+        ..SessionQuery::between(since.as_ref(), None)
+    })?;
+
+    let rows = label_rows(&sessions, by, sort);
+    let total = ReportRow {
+        label: String::from(TOTAL_LABEL),
+        pomodoros: sessions.iter().filter(|s| s.is_completed()).count() as u32,
+        minutes: sessions.iter().map(|s| s.actual_seconds()).sum::<i64>() / 60,
+    };
+
+    let mut stdout = std::io::stdout().lock();
+    match format {
+        ReportFormat::Table => writeln!(stdout, "{}", render_table(by, &rows, &total))?,
+        ReportFormat::Markdown => writeln!(stdout, "{}", render_markdown(by, &rows, &total))?,
+        // the total is left out of CSV, where it'd read as a row for a label named "total" (and
+        // summing the rows double-counts pomodoros with several tags anyway)
+        ReportFormat::Csv => {
+            let mut csv_writer = csv::Writer::from_writer(&mut stdout);
+            csv_writer.write_record([by.as_str(), "pomodoros", "minutes"])?;
+            for row in &rows {
+                csv_writer.write_record([
+                    row.label.as_str(),
+                    &row.pomodoros.to_string(),
+                    &row.minutes.to_string(),
+                ])?;
+            }
+            csv_writer.flush()?;
+        }
+    }
+    stdout.flush()?;
+
+    Ok(())
+}
+
+fn label_rows(sessions: &[Session], by: ReportBy, sort: ReportSort) -> Vec<ReportRow> {
+    let mut totals: HashMap<&str, (u32, i64)> = HashMap::new();
+    for session in sessions {
+        let labels = match by {
+            ReportBy::Task => vec![session.task.as_deref().unwrap_or(NO_TASK_LABEL)],
+            ReportBy::Tag if session.tags.is_empty() => vec![NO_TAG_LABEL],
+            ReportBy::Tag => session.tags.iter().map(String::as_str).collect(),
+        };
+
+        for label in labels {
+            let (pomodoros, seconds) = totals.entry(label).or_default();
+            if session.is_completed() {
+                *pomodoros += 1;
+            }
+            *seconds += session.actual_seconds();
+        }
+    }
+
+    let mut rows = totals
+        .into_iter()
+        .map(|(label, (pomodoros, seconds))| ReportRow {
+            label: label.to_string(),
+            pomodoros,
+            minutes: seconds / 60,
+        })
+        .collect::<Vec<_>>();
+
+    rows.sort_by(|a, b| {
+        match sort {
+            ReportSort::Minutes => b.minutes.cmp(&a.minutes),
+            ReportSort::Pomodoros => b.pomodoros.cmp(&a.pomodoros),
+            ReportSort::Label => std::cmp::Ordering::Equal,
+        }
+        .then_with(|| a.label.cmp(&b.label))
+    });
+
+    rows
+}
+
+fn render_table(by: ReportBy, rows: &[ReportRow], total: &ReportRow) -> String {
+    let header = [by.as_str(), "pomodoros", "minutes"];
+    let label_width = rows
+        .iter()
+        .chain([total])
+        .map(|r| r.label.chars().count())
+        .chain([header[0].len()])
+        .max()
+        .unwrap_or_default();
+
+    let line = |label: &str, pomodoros: &str, minutes: &str| {
+        format!(
+            "{label:<label_width$}  {pomodoros:>width_p$}  {minutes:>width_m$}",
+            width_p = header[1].len(),
+            width_m = header[2].len(),
+        )
+    };
+
+    let mut lines = vec![line(header[0], header[1], header[2])];
+    for row in rows.iter().chain([total]) {
+        lines.push(line(
+            &row.label,
+            &row.pomodoros.to_string(),
+            &row.minutes.to_string(),
+        ));
+    }
+
+    lines.join("\n")
+}
+
+fn render_markdown(by: ReportBy, rows: &[ReportRow], total: &ReportRow) -> String {
+    let mut lines = vec![
+        format!("| {} | pomodoros | minutes |", by.as_str()),
+        String::from("| --- | ---: | ---: |"),
+    ];
+    for row in rows {
+        lines.push(format!(
+            "| {} | {} | {} |",
+            row.label.replace('|', "\\|"),
+            row.pomodoros,
+            row.minutes
+        ));
+    }
+    lines.push(format!(
+        "| **{}** | **{}** | **{}** |",
+        total.label, total.pomodoros, total.minutes
+    ));
+
+    lines.join("\n")
+}
+
+/// Shows a GitHub-style grid of completed pomodoros per day over the last `weeks` weeks (the
+/// current one included), followed by a histogram of the hours of the day they were started in.
+pub fn show_heatmap(
+    store: &dyn Store,
+    now: DateTime<Utc>,
+    weeks: u16,
+    color: ColorMode,
+) -> anyhow::Result<()> {
+    let today = local_date(&now);
+    let first_day = heatmap_start(today, weeks);
+
+    let sessions = store
+        .query_sessions(&SessionQuery {
+            kind: Some(SessionKind::Focus),
+            ..SessionQuery::between(Some(&TimeBound::Date(first_day)), None)
+        })?
+        .into_iter()
+        .filter(|s| s.is_completed())
+        .filter(|s| (first_day..=today).contains(&local_date(&s.start)))
+        .collect::<Vec<_>>();
+
+    let colour = color.enabled();
+    println!(
+        "{}",
+        render_heatmap(&pomodoros_per_day(&sessions), today, weeks, colour)
+    );
+    println!();
+    println!("{}", render_hour_histogram(&hour_counts(&sessions)));
+
+    Ok(())
+}
+
+fn heatmap_start(today: NaiveDate, weeks: u16) -> NaiveDate {
+    week_start(today) - Days::new(7 * (weeks.max(1) as u64 - 1))
+}
+
+/// Maps a day's count to one of the heatmap's levels, relative to the busiest day shown.
+fn heatmap_level(count: u32, max: u32) -> usize {
+    if count == 0 || max == 0 {
+        return 0;
+    }
+
+    (count * 4).div_ceil(max).clamp(1, 4) as usize
+}
+
+fn heatmap_cell(level: usize, colour: bool) -> String {
+    if colour {
+        format!(
+            "{}{}{}",
+            HEATMAP_COLOURS[level], HEATMAP_LEVELS[level], ANSI_RESET
+        )
+    } else {
+        HEATMAP_LEVELS[level].to_string()
+    }
+}
+
+fn render_heatmap(
+    per_day: &BTreeMap<NaiveDate, u32>,
+    today: NaiveDate,
+    weeks: u16,
+    colour: bool,
+) -> String {
+    let first_day = heatmap_start(today, weeks);
+    let weeks = weeks.max(1) as usize;
+    let in_range = per_day.range(first_day..=today).map(|(_, c)| *c);
+    let total = in_range.clone().sum::<u32>();
+    let max = in_range.max().unwrap_or_default();
+
+    let mut lines = vec![];
+
+    // month labels go above the first week that starts in a new month, if there's room (the last
+    // one can stick out past the grid)
+    let mut month_row = vec![' '; ROW_LABEL_WIDTH + weeks * 2 + MONTH_LABEL_WIDTH];
+    let mut next_free_col = 0;
+    for week in 0..weeks {
+        let start = first_day + Days::new(7 * week as u64);
+        let is_new_month = week == 0 || start.month() != (start - Days::new(7)).month();
+        let col = ROW_LABEL_WIDTH + week * 2;
+        let label = start.format("%b").to_string();
+        if is_new_month && col >= next_free_col {
+            for (i, c) in label.chars().enumerate() {
+                month_row[col + i] = c;
+            }
+            next_free_col = col + label.len() + 1;
+        }
+    }
+    lines.push(month_row.into_iter().collect::<String>());
+
+    for (day, weekday) in WEEKDAYS.iter().enumerate() {
+        let mut line = format!("{weekday:<ROW_LABEL_WIDTH$}");
+        for week in 0..weeks {
+            let date = first_day + Days::new((7 * week + day) as u64);
+            if date > today {
+                break;
+            }
+            let count = per_day.get(&date).copied().unwrap_or_default();
+            line.push_str(&heatmap_cell(heatmap_level(count, max), colour));
+            line.push(' ');
+        }
+        lines.push(line);
+    }
+
+    let legend = (0..HEATMAP_LEVELS.len())
+        .map(|l| heatmap_cell(l, colour))
+        .collect::<Vec<_>>()
+        .join(" ");
+    lines.push(format!("{:ROW_LABEL_WIDTH$}less {legend} more", ""));
+    lines.push(String::new());
+    lines.push(format!(
+        "{total} pomodoro{} in the last {weeks} week{}",
+        if total == 1 { "" } else { "s" },
+        if weeks == 1 { "" } else { "s" },
+    ));
+
+    lines
+        .iter()
+        .map(|l| l.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the number of sessions started in each hour of the (local) day.
+fn hour_counts(sessions: &[Session]) -> [u32; 24] {
+    let mut counts = [0; 24];
+    for session in sessions {
+        counts[session.start.with_timezone(&Local).hour() as usize] += 1;
+    }
+
+    counts
+}
+
+/// Renders a histogram of pomodoros by hour of the day, from the first to the last hour that has
+/// any.
+fn render_hour_histogram(counts: &[u32; 24]) -> String {
+    let max = counts.iter().copied().max().unwrap_or_default();
+    let (Some(first), Some(last)) = (
+        counts.iter().position(|c| *c > 0),
+        counts.iter().rposition(|c| *c > 0),
+    ) else {
+        return String::from("no pomodoros in this period");
+    };
+
+    let mut lines = vec![String::from("pomodoros by hour of day")];
+    for (hour, count) in counts.iter().enumerate().take(last + 1).skip(first) {
+        let width = (*count as usize * HISTOGRAM_WIDTH).div_ceil(max as usize);
+        let bar = "█".repeat(width);
+        lines.push(if *count == 0 {
+            format!("{hour:02}:00")
+        } else {
+            format!("{hour:02}:00  {bar} {count}")
+        });
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").expect("date should've been parsed")
+    }
+
+    #[test]
+    fn heatmap_shows_a_grid_of_days_by_week() {
+        // GIVEN
+        let per_day = BTreeMap::from([
+            (date("2025-01-27"), 8),
+            (date("2025-01-29"), 2),
+            (date("2025-02-04"), 4),
+            (date("2025-02-05"), 1),
+        ]);
+        // a wednesday
+        let today = date("2025-02-05");
+
+        // WHEN
+        let got = render_heatmap(&per_day, today, 3, false);
+
+        // THEN
+        assert_eq!(
+            got,
+            "    Jan Feb
+Mon · █ ·
+Tue · · ▒
+Wed · ░ ░
+Thu · ·
+Fri · ·
+Sat · ·
+Sun · ·
+    less · ░ ▒ ▓ █ more
+
+15 pomodoros in the last 3 weeks"
+        );
+    }
+
+    fn session(task: Option<&str>, tags: &[&str], mins: i64) -> Session {
+        let start = DateTime::parse_from_rfc3339("2025-01-06T09:00:00Z")
+            .expect("timestamp should've been parsed")
+            .to_utc();
+        Session {
+            timer: String::from("default"),
+            kind: SessionKind::Focus,
+            start,
+            end: start + chrono::Duration::minutes(mins),
+            planned_mins: Some(25),
+            task: task.map(String::from),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn tag_rows_count_pomodoros_towards_each_of_their_tags() {
+        // GIVEN
+        let sessions = vec![
+            session(Some("JIRA-1"), &["backend", "review"], 25),
+            session(Some("JIRA-1"), &["backend"], 10),
+            session(None, &[], 25),
+        ];
+
+        // WHEN
+        let got = label_rows(&sessions, ReportBy::Tag, ReportSort::Label);
+
+        // THEN
+        let row = |label: &str, pomodoros, minutes| ReportRow {
+            label: label.to_string(),
+            pomodoros,
+            minutes,
+        };
+        assert_eq!(
+            got,
+            vec![
+                row("(no tag)", 1, 25),
+                row("backend", 1, 35),
+                row("review", 1, 25),
+            ]
+        );
+    }
+
+    #[test]
+    fn task_rows_are_sorted_by_minutes_by_default() {
+        // GIVEN
+        let sessions = vec![
+            session(Some("short"), &[], 10),
+            session(Some("long"), &[], 25),
+            session(Some("long"), &[], 25),
+        ];
+
+        // WHEN
+        let got = label_rows(&sessions, ReportBy::Task, ReportSort::Minutes)
+            .into_iter()
+            .map(|r| r.label)
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(got, vec!["long", "short"]);
+    }
+
+    #[test]
+    fn heatmap_levels_are_relative_to_the_busiest_day() {
+        // GIVEN
+        let counts = [0, 1, 4, 5, 8];
+
+        // WHEN
+        let got = counts
+            .iter()
+            .map(|c| heatmap_level(*c, 8))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(got, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn hour_histogram_only_shows_hours_with_pomodoros_in_between() {
+        // GIVEN
+        let mut counts = [0; 24];
+        counts[9] = 4;
+        counts[11] = 2;
+
+        // WHEN
+        let got = render_hour_histogram(&counts);
+
+        // THEN
+        assert_eq!(
+            got,
+            format!(
+                "pomodoros by hour of day\n09:00  {} 4\n10:00\n11:00  {} 2",
+                "█".repeat(40),
+                "█".repeat(20)
+            )
+        );
+    }
+}
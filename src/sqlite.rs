@@ -0,0 +1,607 @@
+use crate::history::{Session, SessionKind, SessionQuery};
+use crate::state::TimerState;
+use crate::store::{FileStore, Store};
+use crate::timer::Timer;
+use anyhow::Context;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{Connection, OpenFlags, Transaction, TransactionBehavior, params, params_from_iter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DATABASE_FILE_SUFFIX: &str = ".db";
+// how long to wait for another tomo process that's writing to the database
+const BUSY_TIMEOUT: Duration = Duration::from_secs(2);
+const SCHEMA_VERSION: i64 = 1;
+const SCHEMA: &str = "
+CREATE TABLE sessions (
+    id INTEGER PRIMARY KEY,
+    timer TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    start TEXT NOT NULL,
+    end TEXT NOT NULL,
+    planned_mins INTEGER,
+    task TEXT
+);
+CREATE UNIQUE INDEX sessions_timer_start ON sessions (timer, start);
+CREATE INDEX sessions_start ON sessions (start);
+
+CREATE TABLE session_tags (
+    session_id INTEGER NOT NULL REFERENCES sessions (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (session_id, position)
+);
+CREATE INDEX session_tags_tag ON session_tags (tag);
+
+CREATE TABLE transitions (
+    id INTEGER PRIMARY KEY,
+    timer TEXT NOT NULL,
+    state TEXT NOT NULL,
+    at TEXT,
+    task TEXT
+);
+CREATE INDEX transitions_timer ON transitions (timer, id);
+";
+
+/// Returns the SQLite database that sits next to a data file (eg. `.tomo.db`).
+pub fn database_path(base_data_file: &Path) -> PathBuf {
+    let mut file_name = base_data_file
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    file_name.push(DATABASE_FILE_SUFFIX);
+
+    base_data_file.with_file_name(file_name)
+}
+
+/// Keeps the history log in a SQLite database, with sessions, their tags, and every state
+/// transition in separate tables. Timers' states are still kept in their data files (as with
+/// [`FileStore`]), so that showing progress doesn't need the database.
+pub struct SqliteStore {
+    files: FileStore,
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// Opens the database next to a data file, creating it if needed.
+    pub fn open(base_data_file: &Path) -> anyhow::Result<Self> {
+        let conn = Connection::open(database_path(base_data_file))
+            .context("couldn't open tomo's database")?;
+
+        Self::with_connection(FileStore::new(base_data_file), conn)
+    }
+
+    /// Opens the database next to a data file without writing to it, so without creating or
+    /// upgrading its schema either.
+    pub fn open_read_only(base_data_file: &Path) -> anyhow::Result<Self> {
+        let conn = Connection::open_with_flags(
+            database_path(base_data_file),
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .context("couldn't open tomo's database")?;
+        conn.busy_timeout(BUSY_TIMEOUT)
+            .context("couldn't set up tomo's database")?;
+
+        if schema_version(&conn)? < SCHEMA_VERSION {
+            return Err(anyhow::anyhow!("tomo's database hasn't been set up"));
+        }
+
+        Ok(Self {
+            files: FileStore::new(base_data_file),
+            conn,
+        })
+    }
+
+    fn with_connection(files: FileStore, conn: Connection) -> anyhow::Result<Self> {
+        conn.pragma_update(None, "foreign_keys", true)
+            .and_then(|_| conn.busy_timeout(BUSY_TIMEOUT))
+            .context("couldn't set up tomo's database")?;
+
+        if schema_version(&conn)? < SCHEMA_VERSION {
+            conn.execute_batch(&format!(
+                "BEGIN;{SCHEMA}PRAGMA user_version = {SCHEMA_VERSION};COMMIT;"
+            ))
+            .context("couldn't create tomo's database")?;
+        }
+
+        Ok(Self { files, conn })
+    }
+
+    /// Adds sessions to the database, skipping ones that are already in it (ie, that started at
+    /// the same time, for the same timer). Returns the number of sessions added.
+    pub fn insert_sessions(&self, sessions: &[Session]) -> anyhow::Result<usize> {
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .context("couldn't write to tomo's database")?;
+        let inserted = insert_rows(&tx, sessions)?;
+        tx.commit().context("couldn't write to tomo's database")?;
+
+        Ok(inserted)
+    }
+
+    fn record_transition(&self, timer: &Timer, state: &TimerState) -> anyhow::Result<()> {
+        let (name, at, task) = match state {
+            TimerState::Stopped { since } => ("stopped", *since, None),
+            TimerState::OnBreak { since } => ("break", *since, None),
+            TimerState::Running(pomodoro) => (
+                "running",
+                Some(pomodoro.started_at),
+                pomodoro.task.as_deref(),
+            ),
+        };
+
+        self.conn
+            .execute(
+                "INSERT INTO transitions (timer, state, at, task) VALUES (?1, ?2, ?3, ?4)",
+                params![timer.name, name, at.as_ref().map(format_time), task],
+            )
+            .context("couldn't write to tomo's database")?;
+
+        Ok(())
+    }
+}
+
+impl Store for SqliteStore {
+    fn read_state(&self, timer: &Timer, strict: bool) -> anyhow::Result<TimerState> {
+        self.files.read_state(timer, strict)
+    }
+
+    fn update_state(
+        &self,
+        timer: &Timer,
+        update: &mut dyn FnMut(&TimerState) -> anyhow::Result<TimerState>,
+    ) -> anyhow::Result<()> {
+        let mut next = None;
+        self.files.update_state(timer, &mut |previous| {
+            let state = update(previous)?;
+            next = Some(state.clone());
+            Ok(state)
+        })?;
+
+        match next {
+            Some(state) => self.record_transition(timer, &state),
+            None => Ok(()),
+        }
+    }
+
+    fn read_sessions(&self) -> anyhow::Result<Vec<Session>> {
+        self.query_sessions(&SessionQuery::default())
+    }
+
+    fn query_sessions(&self, query: &SessionQuery) -> anyhow::Result<Vec<Session>> {
+        let mut conditions = vec![];
+        let mut values = vec![];
+        if let Some(since) = &query.since {
+            conditions.push(String::from("s.start >= ?"));
+            values.push(format_time(since));
+        }
+        if let Some(until) = &query.until {
+            conditions.push(String::from("s.start < ?"));
+            values.push(format_time(until));
+        }
+        if let Some(kind) = query.kind {
+            conditions.push(String::from("s.kind = ?"));
+            values.push(kind.as_str().to_string());
+        }
+        if !query.timers.is_empty() {
+            conditions.push(format!(
+                "s.timer IN ({})",
+                vec!["?"; query.timers.len()].join(", ")
+            ));
+            values.extend(query.timers.iter().cloned());
+        }
+
+        let where_clause = match conditions.as_slice() {
+            [] => String::new(),
+            _ => format!("WHERE {}", conditions.join(" AND ")),
+        };
+        // tags can't contain ',' (see validate_tags), so they're joined with it to read each session's
+        // tags in the same query
+        let mut select_sessions = self.conn.prepare(&format!(
+            "SELECT s.timer, s.kind, s.start, s.end, s.planned_mins, s.task,
+                    group_concat(t.tag, ',' ORDER BY t.position)
+             FROM sessions s LEFT JOIN session_tags t ON t.session_id = s.id
+             {where_clause} GROUP BY s.id ORDER BY s.id"
+        ))?;
+
+        let rows = select_sessions
+            .query_map(params_from_iter(values), |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, Option<i64>>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                    row.get::<_, Option<String>>(6)?,
+                ))
+            })
+            .context("couldn't read from tomo's database")?;
+
+        let mut sessions = vec![];
+        for row in rows {
+            let (timer, kind, start, end, planned_mins, task, tags) =
+                row.context("couldn't read from tomo's database")?;
+            let tags = tags
+                .map(|tags| tags.split(',').map(String::from).collect())
+                .unwrap_or_default();
+
+            sessions.push(Session {
+                timer,
+                kind: parse_kind(&kind)?,
+                start: parse_time(&start)?,
+                end: parse_time(&end)?,
+                planned_mins,
+                task,
+                tags,
+            });
+        }
+
+        Ok(sessions)
+    }
+
+    fn append_sessions(&self, sessions: &[Session]) -> anyhow::Result<()> {
+        let inserted = self.insert_sessions(sessions)?;
+        warn_about_skipped_sessions(sessions.len() - inserted);
+
+        Ok(())
+    }
+
+    fn append_new_sessions(
+        &self,
+        select: &mut dyn FnMut(&[Session]) -> anyhow::Result<Vec<Session>>,
+    ) -> anyhow::Result<usize> {
+        // an immediate transaction takes the database's write lock before anything is read
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)
+            .context("couldn't write to tomo's database")?;
+        let recorded = self.read_sessions()?;
+        let new_sessions = select(&recorded)?;
+        let inserted = insert_rows(&tx, &new_sessions)?;
+        tx.commit().context("couldn't write to tomo's database")?;
+        warn_about_skipped_sessions(new_sessions.len() - inserted);
+
+        Ok(inserted)
+    }
+}
+
+/// Moves the plain history log into the database next to a data file (creating it if needed).
+/// Sessions that are already in the database are skipped (as are ones the plain history log
+/// records twice, for the same timer and start), so this can be run more than once.
+/// From then on, tomo uses the database instead of the plain history log, which is left as is.
+pub fn migrate_history(base_data_file: &Path) -> anyhow::Result<()> {
+    let sessions = FileStore::new(base_data_file).read_sessions()?;
+    let store = SqliteStore::open(base_data_file)?;
+    let migrated = store.insert_sessions(&sessions)?;
+
+    println!(
+        "migrated {migrated} session(s) to {}, skipped {} duplicate(s)",
+        database_path(base_data_file).display(),
+        sessions.len() - migrated
+    );
+
+    Ok(())
+}
+
+/// Warns about sessions that weren't recorded, since the database already has a session for the
+/// same timer that started at the same time (while the plain history log would've kept them).
+fn warn_about_skipped_sessions(num_skipped: usize) {
+    if num_skipped > 0 {
+        eprintln!(
+            "warning: skipped {num_skipped} session(s) that started at the same time as one already \
+             in tomo's database, for the same timer"
+        );
+    }
+}
+
+/// Inserts sessions (and their tags) as part of a transaction, skipping ones that are already in
+/// the database. Returns the number of sessions inserted.
+fn insert_rows(conn: &Connection, sessions: &[Session]) -> anyhow::Result<usize> {
+    let mut insert_session = conn.prepare(
+        "INSERT OR IGNORE INTO sessions (timer, kind, start, end, planned_mins, task)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    let mut insert_tag =
+        conn.prepare("INSERT INTO session_tags (session_id, position, tag) VALUES (?1, ?2, ?3)")?;
+
+    let mut inserted = 0;
+    for session in sessions {
+        let changed = insert_session
+            .execute(params![
+                session.timer,
+                session.kind.as_str(),
+                format_time(&session.start),
+                format_time(&session.end),
+                session.planned_mins,
+                session.task,
+            ])
+            .context("couldn't write to tomo's database")?;
+        if changed == 0 {
+            continue;
+        }
+
+        let id = conn.last_insert_rowid();
+        for (position, tag) in session.tags.iter().enumerate() {
+            insert_tag
+                .execute(params![id, position as i64, tag])
+                .context("couldn't write to tomo's database")?;
+        }
+        inserted += 1;
+    }
+
+    Ok(inserted)
+}
+
+/// Returns the version of the database's schema, failing if it's newer than this version of tomo
+/// knows.
+fn schema_version(conn: &Connection) -> anyhow::Result<i64> {
+    let version: i64 = conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .context("couldn't read the version of tomo's database")?;
+    if version > SCHEMA_VERSION {
+        return Err(anyhow::anyhow!(
+            "tomo's database was created by a newer version of tomo"
+        ));
+    }
+
+    Ok(version)
+}
+
+/// Formats times with a fixed number of digits, so that they sort chronologically as text.
+fn format_time(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+fn parse_time(time: &str) -> anyhow::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time)
+        .map(|t| t.to_utc())
+        .with_context(|| format!("couldn't parse time \"{time}\" from tomo's database"))
+}
+
+fn parse_kind(kind: &str) -> anyhow::Result<SessionKind> {
+    match kind {
+        "focus" => Ok(SessionKind::Focus),
+        "break" => Ok(SessionKind::Break),
+        _ => Err(anyhow::anyhow!(
+            "unknown session kind \"{kind}\" in tomo's database"
+        )),
+    }
+}
+
+/// Opens the database next to a data file if there is one; returns `None` otherwise.
+pub(crate) fn open_if_exists(base_data_file: &Path) -> anyhow::Result<Option<SqliteStore>> {
+    if !database_path(base_data_file).exists() {
+        return Ok(None);
+    }
+
+    SqliteStore::open(base_data_file).map(Some)
+}
+
+/// Like [`open_if_exists`], but opens the database read-only.
+pub(crate) fn open_read_only_if_exists(
+    base_data_file: &Path,
+) -> anyhow::Result<Option<SqliteStore>> {
+    if !database_path(base_data_file).exists() {
+        return Ok(None);
+    }
+
+    SqliteStore::open_read_only(base_data_file).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::track::{start_tracking, stop_tracking};
+    use tempfile::tempdir;
+
+    fn time(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts)
+            .expect("timestamp should've been parsed")
+            .to_utc()
+    }
+
+    fn session(timer: &str, kind: SessionKind, start: &str, tags: &[&str]) -> Session {
+        Session {
+            timer: timer.to_string(),
+            kind,
+            start: time(start),
+            end: time(start) + chrono::Duration::minutes(25),
+            planned_mins: Some(25),
+            task: Some(String::from("JIRA-123")),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    fn in_memory_store(dir: &Path) -> SqliteStore {
+        let conn = Connection::open_in_memory().expect("database should've been opened");
+        SqliteStore::with_connection(FileStore::new(&dir.join(".tomo")), conn)
+            .expect("database should've been set up")
+    }
+
+    #[test]
+    fn sessions_survive_a_round_trip() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let store = in_memory_store(dir.path());
+        let sessions = vec![
+            session(
+                "default",
+                SessionKind::Focus,
+                "2025-01-06T09:00:00.5Z",
+                &["review", "backend"],
+            ),
+            session("pairing", SessionKind::Break, "2025-01-06T09:00:00Z", &[]),
+        ];
+
+        // WHEN
+        store
+            .append_sessions(&sessions)
+            .expect("sessions should've been appended");
+
+        // THEN
+        let got = store.read_sessions().expect("sessions should've been read");
+        assert_eq!(got, sessions);
+    }
+
+    #[test]
+    fn querying_sessions_works() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let store = in_memory_store(dir.path());
+        store
+            .append_sessions(&[
+                session("default", SessionKind::Focus, "2025-01-05T09:00:00Z", &[]),
+                session(
+                    "default",
+                    SessionKind::Focus,
+                    "2025-01-06T09:00:00Z",
+                    &["b", "a"],
+                ),
+                session(
+                    "default",
+                    SessionKind::Break,
+                    "2025-01-06T09:25:00Z",
+                    &["c"],
+                ),
+                session("pairing", SessionKind::Focus, "2025-01-06T10:00:00Z", &[]),
+                session("default", SessionKind::Focus, "2025-01-07T09:00:00Z", &[]),
+            ])
+            .expect("sessions should've been appended");
+        let query = SessionQuery {
+            since: Some(time("2025-01-06T00:00:00Z")),
+            until: Some(time("2025-01-07T00:00:00Z")),
+            kind: Some(SessionKind::Focus),
+            timers: vec![String::from("default")],
+        };
+
+        // WHEN
+        let got = store
+            .query_sessions(&query)
+            .expect("sessions should've been queried");
+
+        // THEN
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].start, time("2025-01-06T09:00:00Z"));
+        assert_eq!(got[0].tags, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn sessions_that_are_already_in_the_database_are_skipped() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let store = in_memory_store(dir.path());
+        let sessions = [
+            session(
+                "default",
+                SessionKind::Focus,
+                "2025-01-06T09:00:00Z",
+                &["a"],
+            ),
+            session("pairing", SessionKind::Focus, "2025-01-06T09:00:00Z", &[]),
+        ];
+        store
+            .insert_sessions(&sessions[..1])
+            .expect("sessions should've been inserted");
+
+        // WHEN
+        let got = store
+            .insert_sessions(&sessions)
+            .expect("sessions should've been inserted");
+
+        // THEN
+        assert_eq!(got, 1);
+        assert_eq!(
+            store
+                .read_sessions()
+                .expect("sessions should've been read")
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn appending_new_sessions_counts_only_the_ones_recorded() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let store = in_memory_store(dir.path());
+        let sessions = [
+            session("default", SessionKind::Focus, "2025-01-06T09:00:00Z", &[]),
+            session("default", SessionKind::Focus, "2025-01-06T09:00:00Z", &[]),
+            session("pairing", SessionKind::Focus, "2025-01-06T09:00:00Z", &[]),
+        ];
+
+        // WHEN
+        let got = store
+            .append_new_sessions(&mut |_| Ok(sessions.to_vec()))
+            .expect("sessions should've been appended");
+
+        // THEN
+        assert_eq!(got, 2);
+    }
+
+    #[test]
+    fn transitions_are_recorded() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let store = in_memory_store(dir.path());
+        let timer =
+            Timer::new(&dir.path().join(".tomo"), None).expect("timer should've been created");
+        let started_at = time("2025-01-06T09:00:00Z");
+
+        // WHEN
+        start_tracking(&store, &timer, started_at, started_at, None, vec![])
+            .expect("tracking should've started");
+        stop_tracking(&store, &timer, time("2025-01-06T09:30:00Z"))
+            .expect("tracking should've stopped");
+
+        // THEN
+        let transitions = store
+            .conn
+            .prepare("SELECT state FROM transitions ORDER BY id")
+            .and_then(|mut s| {
+                s.query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .expect("transitions should've been read");
+        assert_eq!(transitions, vec!["running", "stopped"]);
+        let sessions = store.read_sessions().expect("sessions should've been read");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].end, time("2025-01-06T09:25:00Z"));
+    }
+
+    #[test]
+    fn databases_from_newer_versions_are_rejected() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let conn = Connection::open_in_memory().expect("database should've been opened");
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .expect("version should've been set");
+
+        // WHEN
+        let got = SqliteStore::with_connection(FileStore::new(&dir.path().join(".tomo")), conn);
+
+        // THEN
+        assert!(got.is_err());
+    }
+
+    #[test]
+    fn opening_read_only_leaves_the_database_alone() {
+        // GIVEN
+        let dir = tempdir().expect("temporary directory should've been created");
+        let base_data_file = dir.path().join(".tomo");
+        let conn = Connection::open(database_path(&base_data_file))
+            .expect("database should've been opened");
+        conn.pragma_update(None, "application_id", 1)
+            .expect("database should've been written");
+
+        // WHEN
+        let got = SqliteStore::open_read_only(&base_data_file);
+
+        // THEN
+        assert!(got.is_err());
+        let version: i64 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .expect("version should've been read");
+        assert_eq!(version, 0);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::fs_utils::write_atomically;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+const BREAK_STRING: &str = "break";
+const STOP_STRING: &str = "stop";
+const TASK_KEY: &str = "task";
+const TAGS_KEY: &str = "tags";
+const TAGS_SEPARATOR: char = ',';
+
+/// The state of a timer, as persisted in its data file.
+///
+/// The first line of the data file holds the state itself: either the time a pomodoro was
+/// started at (in RFC 3339 format), or "break"/"stop", optionally followed by the time the break
+/// was started/the timer was stopped at. Further lines hold details about a running pomodoro as
+/// `key: value` pairs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimerState {
+    /// No pomodoro is running
+    Stopped {
+        /// When the timer was stopped; data files from older versions of tomo don't say
+        since: Option<DateTime<Utc>>,
+    },
+    /// A break is running
+    OnBreak {
+        /// When the break started; data files from older versions of tomo don't say
+        since: Option<DateTime<Utc>>,
+    },
+    /// A pomodoro is running
+    Running(Pomodoro),
+}
+
+/// A running pomodoro.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pomodoro {
+    /// When the pomodoro started
+    pub started_at: DateTime<Utc>,
+    /// What's being worked on
+    pub task: Option<String>,
+    /// Labels for grouping the pomodoro in reports
+    pub tags: Vec<String>,
+}
+
+impl TimerState {
+    /// Parses the contents of a data file.
+    pub fn parse(contents: &[u8]) -> anyhow::Result<Self> {
+        let contents = std::str::from_utf8(contents).context("data file is not valid utf-8")?;
+        let mut lines = contents.lines();
+        let status = lines.next().unwrap_or_default().trim();
+
+        if let Some(since) = parse_keyword_with_time(status, STOP_STRING)? {
+            return Ok(Self::Stopped { since });
+        }
+
+        if let Some(since) = parse_keyword_with_time(status, BREAK_STRING)? {
+            return Ok(Self::OnBreak { since });
+        }
+
+        let ts = DateTime::parse_from_rfc3339(status)
+            .context("couldn't not parse time from tomo's data file")?;
+
+        let mut pomodoro = Pomodoro {
+            started_at: ts.to_utc(),
+            task: None,
+            tags: vec![],
+        };
+
+        // keys this version of tomo doesn't know about are ignored
+        for line in lines {
+            match line.split_once(':') {
+                Some((TASK_KEY, task)) => pomodoro.task = Some(task.trim().to_string()),
+                Some((TAGS_KEY, tags)) => {
+                    pomodoro.tags = tags
+                        .split(TAGS_SEPARATOR)
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(String::from)
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self::Running(pomodoro))
+    }
+
+    /// Returns the contents of a data file that holds this state.
+    pub fn to_data_file_contents(&self) -> String {
+        match self {
+            Self::Stopped { since } => keyword_with_time(STOP_STRING, since.as_ref()),
+            Self::OnBreak { since } => keyword_with_time(BREAK_STRING, since.as_ref()),
+            Self::Running(pomodoro) => {
+                let mut contents = pomodoro.started_at.to_rfc3339();
+                if let Some(task) = &pomodoro.task {
+                    contents.push_str(&format!("\n{TASK_KEY}: {task}"));
+                }
+                if !pomodoro.tags.is_empty() {
+                    contents.push_str(&format!(
+                        "\n{TAGS_KEY}: {}",
+                        pomodoro.tags.join(&TAGS_SEPARATOR.to_string())
+                    ));
+                }
+                contents
+            }
+        }
+    }
+}
+
+// older versions of tomo wrote "break"/"stop" without a time
+fn parse_keyword_with_time(
+    status: &str,
+    keyword: &str,
+) -> anyhow::Result<Option<Option<DateTime<Utc>>>> {
+    let Some(rest) = status.strip_prefix(keyword) else {
+        return Ok(None);
+    };
+
+    if rest.is_empty() {
+        return Ok(Some(None));
+    }
+
+    let Some(ts) = rest.strip_prefix(' ') else {
+        return Ok(None);
+    };
+
+    let ts = DateTime::parse_from_rfc3339(ts.trim())
+        .with_context(|| format!("couldn't not parse time of {keyword} from tomo's data file"))?;
+
+    Ok(Some(Some(ts.to_utc())))
+}
+
+fn keyword_with_time(keyword: &str, time: Option<&DateTime<Utc>>) -> String {
+    match time {
+        Some(t) => format!("{keyword} {}", t.to_rfc3339()),
+        None => keyword.to_string(),
+    }
+}
+
+/// Reads a timer's state from its data file; callers are expected to hold the data file's lock.
+///
+/// Unless `strict` is set, a missing data file is treated as a stopped timer, and so is a corrupt
+/// one.
+pub fn read_state(file_path: &Path, strict: bool) -> anyhow::Result<TimerState> {
+    let contents = match fs::read(file_path) {
+        Ok(c) => c,
+        Err(e) if !strict && e.kind() == ErrorKind::NotFound => {
+            return Ok(TimerState::Stopped { since: None });
+        }
+        Err(e) => return Err(e).context("couldn't read from tomo's data file"),
+    };
+
+    // a corrupt data file (eg. one truncated by a crash from before writes were atomic) shouldn't
+    // keep breaking the status line; it's reset to "stop" so that the warning is only shown once
+    match TimerState::parse(&contents) {
+        Ok(s) => Ok(s),
+        Err(e) if strict => Err(e),
+        Err(e) => {
+            eprintln!("warning: tomo's data file is corrupt, treating timer as stopped: {e:#}");
+            let state = TimerState::Stopped { since: None };
+            let _ = write_state(file_path, &state);
+            Ok(state)
+        }
+    }
+}
+
+/// Writes a timer's state to its data file; callers are expected to hold the data file's lock.
+pub fn write_state(file_path: &Path, state: &TimerState) -> anyhow::Result<()> {
+    write_atomically(file_path, state.to_data_file_contents().as_bytes())
+        .context("couldn't write to tomo's data file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts)
+            .expect("timestamp should've been parsed")
+            .to_utc()
+    }
+
+    #[test]
+    fn parsing_legacy_contents_works() {
+        // GIVEN
+        // WHEN
+        let stopped = TimerState::parse(b"stop").expect("state should've been parsed");
+        let on_break = TimerState::parse(b"break").expect("state should've been parsed");
+        let running =
+            TimerState::parse(b"2025-01-01T10:30:00+00:00\n").expect("state should've been parsed");
+
+        // THEN
+        assert_eq!(stopped, TimerState::Stopped { since: None });
+        assert_eq!(on_break, TimerState::OnBreak { since: None });
+        assert_eq!(
+            running,
+            TimerState::Running(Pomodoro {
+                started_at: time("2025-01-01T10:30:00Z"),
+                task: None,
+                tags: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn state_survives_a_round_trip() {
+        // GIVEN
+        let states = [
+            TimerState::Stopped {
+                since: Some(time("2025-01-01T10:30:00Z")),
+            },
+            TimerState::OnBreak {
+                since: Some(time("2025-01-01T10:30:00Z")),
+            },
+            TimerState::Running(Pomodoro {
+                started_at: time("2025-01-01T10:30:00Z"),
+                task: Some(String::from("JIRA-123: fix login")),
+                tags: vec![String::from("backend"), String::from("review")],
+            }),
+        ];
+
+        // WHEN
+        let got = states
+            .iter()
+            .map(|s| TimerState::parse(s.to_data_file_contents().as_bytes()))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .expect("states should've been parsed");
+
+        // THEN
+        assert_eq!(got, states);
+    }
+
+    #[test]
+    fn unknown_keys_are_ignored() {
+        // GIVEN
+        let contents = "2025-01-01T10:30:00+00:00\ntask: write docs\nmood: great";
+
+        // WHEN
+        let got = TimerState::parse(contents.as_bytes()).expect("state should've been parsed");
+
+        // THEN
+        assert_eq!(
+            got,
+            TimerState::Running(Pomodoro {
+                started_at: time("2025-01-01T10:30:00Z"),
+                task: Some(String::from("write docs")),
+                tags: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn parsing_truncated_contents_fails() {
+        // GIVEN
+        // WHEN
+        let got = TimerState::parse(b"2025-01-01T10:3");
+
+        // THEN
+        assert!(got.is_err());
+    }
+}
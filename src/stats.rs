@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+use tomo::config::Config;
+use tomo::goals::{local_date, pomodoros_in_week, pomodoros_per_day, week_start};
+use tomo::history::{SessionKind, SessionQuery};
+use tomo::store::Store;
+
+/// How often a goal was met over a number of consecutive periods (days or weeks).
+#[derive(Debug, PartialEq)]
+struct GoalStats {
+    met: u32,
+    total: u32,
+    current_streak: u32,
+    longest_streak: u32,
+}
+
+/// Computes goal stats from the pomodoro counts of consecutive periods, oldest first. The last
+/// period is the current one, which is still in progress; it only counts once the goal is met.
+fn goal_stats(counts: &[u32], goal: u32) -> GoalStats {
+    let mut stats = GoalStats {
+        met: 0,
+        total: 0,
+        current_streak: 0,
+        longest_streak: 0,
+    };
+
+    for (i, count) in counts.iter().enumerate() {
+        let is_current = i == counts.len() - 1;
+        if *count >= goal {
+            stats.met += 1;
+            stats.total += 1;
+            stats.current_streak += 1;
+            stats.longest_streak = stats.longest_streak.max(stats.current_streak);
+        } else if !is_current {
+            stats.total += 1;
+            stats.current_streak = 0;
+        }
+    }
+
+    stats
+}
+
+pub fn show_stats(store: &dyn Store, now: DateTime<Utc>, config: &Config) -> anyhow::Result<()> {
+    let per_day = pomodoros_per_day(&store.query_sessions(&SessionQuery {
+        kind: Some(SessionKind::Focus),
+        ..SessionQuery::default()
+    })?);
+    let today = local_date(&now);
+    let first_day = per_day.keys().next().copied().unwrap_or(today).min(today);
+
+    let days = first_day
+        .iter_days()
+        .take_while(|d| *d <= today)
+        .collect::<Vec<_>>();
+    let daily_counts = days
+        .iter()
+        .map(|d| per_day.get(d).copied().unwrap_or_default())
+        .collect::<Vec<_>>();
+    let weekly_counts = days
+        .iter()
+        .filter(|d| **d == week_start(**d) || **d == first_day)
+        .map(|d| pomodoros_in_week(&per_day, *d))
+        .collect::<Vec<_>>();
+
+    let today_count = daily_counts.last().copied().unwrap_or_default();
+    let week_count = weekly_counts.last().copied().unwrap_or_default();
+    let goal_or_dash = |g: Option<u32>| g.map_or(String::from("-"), |g| g.to_string());
+
+    let mut rows = vec![
+        (
+            "today",
+            format!("{today_count}/{}", goal_or_dash(config.daily_goal)),
+        ),
+        (
+            "this week",
+            format!("{week_count}/{}", goal_or_dash(config.weekly_goal)),
+        ),
+    ];
+
+    match config.daily_goal {
+        Some(goal) => {
+            let stats = goal_stats(&daily_counts, goal);
+            rows.push((
+                "daily goal met",
+                format!(
+                    "{} of {} ({})",
+                    stats.met,
+                    plural(stats.total, "day"),
+                    percentage(stats.met, stats.total)
+                ),
+            ));
+            rows.push((
+                "daily streak",
+                format!(
+                    "{} (longest: {})",
+                    plural(stats.current_streak, "day"),
+                    plural(stats.longest_streak, "day")
+                ),
+            ));
+        }
+        None => rows.push(("daily goal", String::from("not set"))),
+    }
+
+    match config.weekly_goal {
+        Some(goal) => {
+            let stats = goal_stats(&weekly_counts, goal);
+            rows.push((
+                "weekly goal met",
+                format!(
+                    "{} of {} ({})",
+                    stats.met,
+                    plural(stats.total, "week"),
+                    percentage(stats.met, stats.total)
+                ),
+            ));
+            rows.push((
+                "weekly streak",
+                format!(
+                    "{} (longest: {})",
+                    plural(stats.current_streak, "week"),
+                    plural(stats.longest_streak, "week")
+                ),
+            ));
+        }
+        None => rows.push(("weekly goal", String::from("not set"))),
+    }
+
+    let width = rows.iter().map(|(l, _)| l.len()).max().unwrap_or_default();
+    for (label, value) in rows {
+        println!("{label:<width$}  {value}");
+    }
+
+    Ok(())
+}
+
+fn plural(count: u32, unit: &str) -> String {
+    if count == 1 {
+        format!("{count} {unit}")
+    } else {
+        format!("{count} {unit}s")
+    }
+}
+
+fn percentage(part: u32, total: u32) -> String {
+    if total == 0 {
+        return String::from("-");
+    }
+
+    format!("{}%", (part * 100 + total / 2) / total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn goal_stats_count_streaks() {
+        // GIVEN
+        let counts = [8, 9, 2, 8, 8, 8, 3];
+
+        // WHEN
+        let got = goal_stats(&counts, 8);
+
+        // THEN
+        assert_eq!(
+            got,
+            GoalStats {
+                met: 5,
+                total: 6,
+                current_streak: 3,
+                longest_streak: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn goal_stats_break_streaks_on_missed_periods() {
+        // GIVEN
+        let counts = [8, 8, 0, 8];
+
+        // WHEN
+        let got = goal_stats(&counts, 8);
+
+        // THEN
+        assert_eq!(
+            got,
+            GoalStats {
+                met: 3,
+                total: 4,
+                current_streak: 1,
+                longest_streak: 2,
+            }
+        );
+    }
+}
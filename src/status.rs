@@ -0,0 +1,168 @@
+use chrono::{DateTime, Duration, Local, Utc};
+use tomo::config::Config;
+use tomo::goals::{local_date, pomodoros_per_day};
+use tomo::history::{SessionKind, SessionQuery, TimeBound};
+use tomo::state::TimerState;
+use tomo::store::Store;
+use tomo::timer::Timer;
+use tomo::track::{CLOCK_SKEW_TOLERANCE_SECS, POMODORO_MINS, POMODOROS_PER_CYCLE, format_duration};
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Prints a summary of a timer's state, one field per line, for reading in a terminal (or with a
+/// screen reader) rather than in a status bar.
+pub fn show_status(
+    store: &dyn Store,
+    timer: &Timer,
+    now: DateTime<Utc>,
+    config: &Config,
+    strict: bool,
+) -> anyhow::Result<()> {
+    let state = store.read_state(timer, strict)?;
+    let today = local_date(&now);
+    let per_day = pomodoros_per_day(&store.query_sessions(&SessionQuery {
+        kind: Some(SessionKind::Focus),
+        ..SessionQuery::between(Some(&TimeBound::Date(today)), None)
+    })?);
+    let completed_today = per_day.get(&today).copied().unwrap_or_default();
+
+    let rows = status_rows(&timer.name, &state, now, completed_today, config.daily_goal);
+    let width = rows.iter().map(|(l, _)| l.len()).max().unwrap_or_default();
+    for (label, value) in rows {
+        println!("{label:<width$}  {value}");
+    }
+
+    Ok(())
+}
+
+fn status_rows(
+    timer_name: &str,
+    state: &TimerState,
+    now: DateTime<Utc>,
+    completed_today: u32,
+    daily_goal: Option<u32>,
+) -> Vec<(&'static str, String)> {
+    let mut rows = vec![("timer", timer_name.to_string())];
+    let since = match state {
+        TimerState::Running(pomodoro) => {
+            let elapsed = (now - pomodoro.started_at).num_seconds();
+            let remaining = POMODORO_MINS * 60 - elapsed.max(0);
+            let state = if remaining > 0 { "running" } else { "finished" };
+
+            rows.push(("state", state.to_string()));
+            rows.push(("started at", local_time(pomodoro.started_at)));
+            rows.push(("elapsed", format_duration(elapsed.max(0))));
+            // rounded up (unlike elapsed), so that the two add up to the pomodoro's length
+            rows.push((
+                "remaining",
+                format_duration((remaining.max(0) + 59) / 60 * 60),
+            ));
+            rows.push((
+                "planned end",
+                local_time(pomodoro.started_at + Duration::minutes(POMODORO_MINS)),
+            ));
+            rows.push(("task", pomodoro.task.clone().unwrap_or(String::from("-"))));
+            rows.push((
+                "tags",
+                match pomodoro.tags.as_slice() {
+                    [] => String::from("-"),
+                    tags => tags.join(", "),
+                },
+            ));
+            Some(pomodoro.started_at)
+        }
+        TimerState::OnBreak { since: break_since } => {
+            rows.push(("state", String::from("on a break")));
+            if let Some(break_since) = break_since {
+                rows.push(("started at", local_time(*break_since)));
+                rows.push((
+                    "elapsed",
+                    format_duration((now - *break_since).num_seconds().max(0)),
+                ));
+            }
+            *break_since
+        }
+        TimerState::Stopped {
+            since: stopped_since,
+        } => {
+            rows.push(("state", String::from("stopped")));
+            if let Some(stopped_since) = stopped_since {
+                rows.push(("stopped at", local_time(*stopped_since)));
+                rows.push((
+                    "idle for",
+                    format_duration((now - *stopped_since).num_seconds().max(0)),
+                ));
+            }
+            *stopped_since
+        }
+    };
+
+    rows.push((
+        "cycle",
+        format!(
+            "pomodoro {} of {POMODOROS_PER_CYCLE}",
+            completed_today % POMODOROS_PER_CYCLE + 1
+        ),
+    ));
+    rows.push((
+        "today",
+        match daily_goal {
+            Some(goal) => format!("{completed_today} of {goal} pomodoros"),
+            None => format!("{completed_today} pomodoro(s)"),
+        },
+    ));
+
+    if let Some(since) = since
+        && (since - now).num_seconds() > CLOCK_SKEW_TOLERANCE_SECS
+    {
+        rows.push((
+            "warning",
+            format!(
+                "this is {} in the future; run tomo doctor",
+                format_duration((since - now).num_seconds())
+            ),
+        ));
+    }
+
+    rows
+}
+
+fn local_time(time: DateTime<Utc>) -> String {
+    time.with_timezone(&Local).format(TIME_FORMAT).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tomo::state::Pomodoro;
+
+    fn time(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339)
+            .expect("timestamp should've been parsed")
+            .to_utc()
+    }
+
+    #[test]
+    fn status_rows_warn_about_start_times_in_the_future() {
+        // GIVEN
+        let state = TimerState::Running(Pomodoro {
+            started_at: time("2025-01-06T11:05:00Z"),
+            task: None,
+            tags: vec![],
+        });
+
+        // WHEN
+        let got = status_rows("default", &state, time("2025-01-06T09:00:00Z"), 0, None);
+
+        // THEN
+        assert_eq!(got[3], ("elapsed", String::from("0m")));
+        assert_eq!(got[4], ("remaining", String::from("25m")));
+        assert_eq!(
+            got.last(),
+            Some(&(
+                "warning",
+                String::from("this is 2h 5m in the future; run tomo doctor")
+            ))
+        );
+    }
+}
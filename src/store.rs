@@ -0,0 +1,235 @@
+use crate::history::{
+    Session, SessionQuery, append_sessions, drop_cut_off_line, ends_with_newline,
+    history_file_path, read_sessions,
+};
+use crate::lock::DataFileLock;
+use crate::state::{TimerState, read_state, write_state};
+use crate::timer::Timer;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Where timers' states and the history log are kept.
+pub trait Store {
+    /// Reads a timer's state. Unless `strict` is set, a timer without a state is treated as
+    /// stopped.
+    fn read_state(&self, timer: &Timer, strict: bool) -> anyhow::Result<TimerState>;
+
+    /// Replaces a timer's state with the one `update` returns for its current state. The timer's
+    /// state can't change in between, even from another process.
+    fn update_state(
+        &self,
+        timer: &Timer,
+        update: &mut dyn FnMut(&TimerState) -> anyhow::Result<TimerState>,
+    ) -> anyhow::Result<()>;
+
+    /// Reads all sessions from the history log, in the order they were recorded.
+    fn read_sessions(&self) -> anyhow::Result<Vec<Session>>;
+
+    /// Reads the sessions that match a query, in the order they were recorded. Backends that can
+    /// look sessions up efficiently (eg. with an index) should override this.
+    fn query_sessions(&self, query: &SessionQuery) -> anyhow::Result<Vec<Session>> {
+        Ok(self
+            .read_sessions()?
+            .into_iter()
+            .filter(|s| query.matches(s))
+            .collect())
+    }
+
+    /// Appends sessions to the history log. Backends that can't record two sessions with the same
+    /// timer and start (eg. SQLite, which has a unique index on them) skip such sessions with a
+    /// warning; the others keep every session.
+    fn append_sessions(&self, sessions: &[Session]) -> anyhow::Result<()>;
+
+    /// Appends the sessions that `select` returns for the sessions already in the history log (eg.
+    /// the ones that aren't duplicates), and returns how many of them were appended (see
+    /// [`Store::append_sessions`] for the ones that aren't). No sessions can be appended in
+    /// between, even from another process.
+    fn append_new_sessions(
+        &self,
+        select: &mut dyn FnMut(&[Session]) -> anyhow::Result<Vec<Session>>,
+    ) -> anyhow::Result<usize>;
+}
+
+/// Opens the store tomo uses for a data file. With the `sqlite` feature, that's the SQLite database
+/// next to it, once `tomo migrate` has created one; otherwise, it's the data and history files.
+pub fn open_store(base_data_file: &Path) -> anyhow::Result<Box<dyn Store>> {
+    #[cfg(feature = "sqlite")]
+    if let Some(store) = crate::sqlite::open_if_exists(base_data_file)? {
+        return Ok(Box::new(store));
+    }
+
+    Ok(Box::new(FileStore::new(base_data_file)))
+}
+
+/// Like [`open_store`], but never creates or upgrades the SQLite database, nor writes to it. For
+/// inspecting tomo's data (eg. in `tomo doctor`) without changing it.
+pub fn open_store_read_only(base_data_file: &Path) -> anyhow::Result<Box<dyn Store>> {
+    #[cfg(feature = "sqlite")]
+    if let Some(store) = crate::sqlite::open_read_only_if_exists(base_data_file)? {
+        return Ok(Box::new(store));
+    }
+
+    Ok(Box::new(FileStore::read_only(base_data_file)))
+}
+
+/// Keeps each timer's state in its data file, and the history log next to the default timer's
+/// data file. This is what tomo itself uses. Appends to the history log are serialised via a lock
+/// of their own, since they come from all timers.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    history_file_path: PathBuf,
+    read_only: bool,
+}
+
+impl FileStore {
+    /// Returns a store for the timers next to `base_data_file` (the default timer's data file).
+    pub fn new(base_data_file: &Path) -> Self {
+        Self {
+            history_file_path: history_file_path(base_data_file),
+            read_only: false,
+        }
+    }
+
+    /// Like [`FileStore::new`], but reading never repairs the history log.
+    pub fn read_only(base_data_file: &Path) -> Self {
+        Self {
+            read_only: true,
+            ..Self::new(base_data_file)
+        }
+    }
+}
+
+impl Store for FileStore {
+    fn read_state(&self, timer: &Timer, strict: bool) -> anyhow::Result<TimerState> {
+        // on a fresh machine there's no data file yet (nor maybe a directory for the lock file)
+        if !strict && !timer.data_file_path.exists() {
+            return Ok(TimerState::Stopped { since: None });
+        }
+
+        // reading the data file can also write to it (when it's corrupt), so readers take the
+        // same exclusive lock as writers
+        let _lock = DataFileLock::acquire(&timer.data_file_path)?;
+
+        read_state(&timer.data_file_path, strict)
+    }
+
+    fn update_state(
+        &self,
+        timer: &Timer,
+        update: &mut dyn FnMut(&TimerState) -> anyhow::Result<TimerState>,
+    ) -> anyhow::Result<()> {
+        let _lock = DataFileLock::acquire(&timer.data_file_path)?;
+        let previous = read_state(&timer.data_file_path, false)?;
+
+        write_state(&timer.data_file_path, &update(&previous)?)
+    }
+
+    fn read_sessions(&self) -> anyhow::Result<Vec<Session>> {
+        let sessions = read_sessions(&self.history_file_path)?;
+
+        // a cut-off last line is dropped, so that the warning about it is only shown once
+        if !self.read_only && !ends_with_newline(&self.history_file_path)? {
+            let _ = DataFileLock::acquire(&self.history_file_path)
+                .and_then(|_lock| drop_cut_off_line(&self.history_file_path));
+        }
+
+        Ok(sessions)
+    }
+
+    fn append_sessions(&self, sessions: &[Session]) -> anyhow::Result<()> {
+        let _lock = DataFileLock::acquire(&self.history_file_path)?;
+
+        append_sessions(&self.history_file_path, sessions)
+    }
+
+    fn append_new_sessions(
+        &self,
+        select: &mut dyn FnMut(&[Session]) -> anyhow::Result<Vec<Session>>,
+    ) -> anyhow::Result<usize> {
+        let _lock = DataFileLock::acquire(&self.history_file_path)?;
+        let recorded = read_sessions(&self.history_file_path)?;
+        let new_sessions = select(&recorded)?;
+        append_sessions(&self.history_file_path, &new_sessions)?;
+
+        Ok(new_sessions.len())
+    }
+}
+
+/// Keeps everything in memory, eg. for tests, or for embedding tomo's state machine somewhere
+/// that has no use for files. Timers are told apart by name.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    states: Mutex<HashMap<String, TimerState>>,
+    sessions: Mutex<Vec<Session>>,
+}
+
+impl MemoryStore {
+    /// Returns a store without any timers or sessions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Store for MemoryStore {
+    fn read_state(&self, timer: &Timer, strict: bool) -> anyhow::Result<TimerState> {
+        let states = self.states.lock().expect("states shouldn't be poisoned");
+
+        match states.get(&timer.name) {
+            Some(state) => Ok(state.clone()),
+            None if strict => Err(anyhow::anyhow!(
+                "timer \"{}\" doesn't have a state",
+                timer.name
+            )),
+            None => Ok(TimerState::Stopped { since: None }),
+        }
+    }
+
+    fn update_state(
+        &self,
+        timer: &Timer,
+        update: &mut dyn FnMut(&TimerState) -> anyhow::Result<TimerState>,
+    ) -> anyhow::Result<()> {
+        let mut states = self.states.lock().expect("states shouldn't be poisoned");
+        let previous = states
+            .get(&timer.name)
+            .cloned()
+            .unwrap_or(TimerState::Stopped { since: None });
+
+        states.insert(timer.name.clone(), update(&previous)?);
+
+        Ok(())
+    }
+
+    fn read_sessions(&self) -> anyhow::Result<Vec<Session>> {
+        Ok(self
+            .sessions
+            .lock()
+            .expect("sessions shouldn't be poisoned")
+            .clone())
+    }
+
+    fn append_sessions(&self, sessions: &[Session]) -> anyhow::Result<()> {
+        self.sessions
+            .lock()
+            .expect("sessions shouldn't be poisoned")
+            .extend_from_slice(sessions);
+
+        Ok(())
+    }
+
+    fn append_new_sessions(
+        &self,
+        select: &mut dyn FnMut(&[Session]) -> anyhow::Result<Vec<Session>>,
+    ) -> anyhow::Result<usize> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .expect("sessions shouldn't be poisoned");
+        let new_sessions = select(&sessions)?;
+        let num_appended = new_sessions.len();
+        sessions.extend(new_sessions);
+
+        Ok(num_appended)
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Duration, Utc};
+use std::time::Instant;
+
+/// Environment variable that sets the current time, same as --now
+pub const NOW_ENV_VAR: &str = "TOMO_NOW";
+
+/// Where tomo gets the current time from: the system clock, or a given moment (eg. for tests, or
+/// to replay a day), from which time moves on as usual.
+#[derive(Debug, Clone, Copy)]
+pub enum TimeSource {
+    /// The system clock
+    System,
+    /// A given moment, plus however long has passed since
+    StartingAt {
+        /// The moment time starts from
+        start: DateTime<Utc>,
+        /// When that moment was given, on the system's monotonic clock
+        started: Instant,
+    },
+}
+
+impl TimeSource {
+    /// Returns a time source that starts from `start`, now.
+    pub fn starting_at(start: DateTime<Utc>) -> Self {
+        Self::StartingAt {
+            start,
+            started: Instant::now(),
+        }
+    }
+
+    /// Returns the current time, according to this source.
+    pub fn now(&self) -> DateTime<Utc> {
+        match self {
+            Self::System => Utc::now(),
+            Self::StartingAt { start, started } => {
+                *start + Duration::from_std(started.elapsed()).unwrap_or_default()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_moves_on_from_a_given_moment() {
+        // GIVEN
+        let start = DateTime::parse_from_rfc3339("2025-01-06T09:00:00Z")
+            .expect("timestamp should've been parsed")
+            .to_utc();
+        let time = TimeSource::starting_at(start);
+
+        // WHEN
+        let got = time.now();
+
+        // THEN
+        assert!(got >= start);
+        assert!(got < start + Duration::seconds(5));
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::Context;
+use dirs::data_dir;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DATA_DIR: &str = "tomo";
+const DATA_FILE: &str = ".tomo";
+/// Name of the timer used when none is given
+pub const DEFAULT_TIMER_NAME: &str = "default";
+const TIMER_NAME_MAX_LEN: usize = 64;
+
+/// A timer, default or named, and the data file that holds its state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timer {
+    /// The timer's name ([`DEFAULT_TIMER_NAME`] for the default timer)
+    pub name: String,
+    /// Where the timer's state is kept
+    pub data_file_path: PathBuf,
+}
+
+impl Timer {
+    /// Returns the timer called `name` (or the default timer, for `None`). Named timers' data
+    /// files are kept next to `base_data_file`, the default timer's data file. Fails if the name
+    /// isn't valid.
+    pub fn new(base_data_file: &Path, name: Option<&str>) -> anyhow::Result<Self> {
+        let name = match name {
+            None => DEFAULT_TIMER_NAME,
+            Some(n) => {
+                validate_timer_name(n)?;
+                n
+            }
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            data_file_path: timer_file_path(base_data_file, name),
+        })
+    }
+}
+
+/// Returns the data file used when none is specified: <YOUR_DATA_DIR>/tomo/.tomo
+pub fn default_data_file_path() -> PathBuf {
+    let user_data_dir = data_dir().unwrap_or(PathBuf::from("."));
+
+    user_data_dir
+        .join(PathBuf::from(DATA_DIR))
+        .join(PathBuf::from(DATA_FILE))
+}
+
+/// Checks that a timer's name is fit for a file name: not empty, not too long, and made of
+/// alphanumeric characters, '-' and '_'.
+pub fn validate_timer_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty() || name.len() > TIMER_NAME_MAX_LEN {
+        return Err(anyhow::anyhow!(
+            "timer name needs to be between 1 and {} characters long",
+            TIMER_NAME_MAX_LEN
+        ));
+    }
+
+    if !name.chars().all(is_valid_timer_name_char) {
+        return Err(anyhow::anyhow!(
+            "timer name can only contain alphanumeric characters, '-', and '_'"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the data file for a timer; named timers live next to the default data file, with the
+/// timer's name appended to the file name (eg. `.tomo-pairing`).
+fn timer_file_path(base_data_file: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_TIMER_NAME {
+        return base_data_file.to_path_buf();
+    }
+
+    let mut file_name = base_data_file
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    file_name.push(format!("-{name}"));
+
+    base_data_file.with_file_name(file_name)
+}
+
+/// Returns all timers that have a data file next to the default data file, sorted by name, with
+/// the default timer first.
+pub fn list_timers(base_data_file: &Path) -> anyhow::Result<Vec<Timer>> {
+    let Some(base_file_name) = base_data_file.file_name().and_then(|n| n.to_str()) else {
+        return Ok(vec![]);
+    };
+    let dir = match base_data_file.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+
+    let mut timers = vec![];
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(timers),
+        Err(e) => return Err(e).context("couldn't read tomo's data directory"),
+    };
+
+    let prefix = format!("{base_file_name}-");
+    for entry in entries {
+        let entry = entry.context("couldn't read tomo's data directory")?;
+        let file_name = entry.file_name();
+        let Some(file_name) = file_name.to_str() else {
+            continue;
+        };
+
+        let name = if file_name == base_file_name {
+            DEFAULT_TIMER_NAME
+        } else {
+            match file_name.strip_prefix(&prefix) {
+                Some(n) if n != DEFAULT_TIMER_NAME && validate_timer_name(n).is_ok() => n,
+                _ => continue,
+            }
+        };
+
+        if !entry.path().is_file() {
+            continue;
+        }
+
+        timers.push(Timer {
+            name: name.to_string(),
+            data_file_path: entry.path(),
+        });
+    }
+
+    timers.sort_by(|a, b| {
+        (a.name != DEFAULT_TIMER_NAME)
+            .cmp(&(b.name != DEFAULT_TIMER_NAME))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    Ok(timers)
+}
+
+fn is_valid_timer_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_timer_uses_base_data_file() {
+        // GIVEN
+        let base = PathBuf::from("/data/tomo/.tomo");
+
+        // WHEN
+        let got = Timer::new(&base, None).expect("timer should've been created");
+
+        // THEN
+        assert_eq!(got.name, DEFAULT_TIMER_NAME);
+        assert_eq!(got.data_file_path, base);
+    }
+
+    #[test]
+    fn named_timer_uses_suffixed_data_file() {
+        // GIVEN
+        let base = PathBuf::from("/data/tomo/.tomo");
+
+        // WHEN
+        let got = Timer::new(&base, Some("pairing")).expect("timer should've been created");
+
+        // THEN
+        assert_eq!(
+            got.data_file_path,
+            PathBuf::from("/data/tomo/.tomo-pairing")
+        );
+    }
+
+    #[test]
+    fn timer_names_with_invalid_characters_are_rejected() {
+        // GIVEN
+        let base = PathBuf::from("/data/tomo/.tomo");
+
+        // WHEN
+        let got = ["", "a/b", "a.b", "a b", "../x"]
+            .iter()
+            .map(|n| Timer::new(&base, Some(n)).is_err())
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert!(got.iter().all(|e| *e));
+    }
+}
@@ -0,0 +1,84 @@
+use std::env;
+use std::process::Command;
+
+/// Environment variable tmux sets for the processes it runs
+pub const TMUX_ENV_VAR: &str = "TMUX";
+const SESSION_TIMER_PREFIX: &str = "tmux-";
+const SESSION_NAME_MAX_LEN: usize = 48;
+
+/// Returns the name of the tmux session tomo is running in, if any.
+pub fn current_session() -> Option<String> {
+    if env::var_os(TMUX_ENV_VAR).is_none_or(|v| v.is_empty()) {
+        return None;
+    }
+
+    let output = Command::new("tmux")
+        .args(["display-message", "-p", "#S"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let session = String::from_utf8(output.stdout).ok()?;
+    let session = session.trim();
+
+    if session.is_empty() {
+        return None;
+    }
+
+    Some(session.to_string())
+}
+
+/// Returns the name of the timer that holds state for a tmux session. Characters that aren't
+/// allowed in timer names are replaced with '_'.
+pub fn session_timer_name(session: &str) -> String {
+    let sanitized = session
+        .chars()
+        .take(SESSION_NAME_MAX_LEN)
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    format!("{SESSION_TIMER_PREFIX}{sanitized}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::validate_timer_name;
+
+    #[test]
+    fn session_timer_name_is_prefixed() {
+        // GIVEN
+        // WHEN
+        let got = session_timer_name("work");
+
+        // THEN
+        assert_eq!(got, "tmux-work");
+    }
+
+    #[test]
+    fn session_timer_name_is_always_a_valid_timer_name() {
+        // GIVEN
+        let sessions = ["my project", "a/b.c", "ü:1", &"x".repeat(200)];
+
+        // WHEN
+        let got = sessions
+            .iter()
+            .map(|s| session_timer_name(s))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(got[0], "tmux-my_project");
+        assert_eq!(got[1], "tmux-a_b_c");
+        assert_eq!(got[2], "tmux-__1");
+        assert!(got.iter().all(|n| validate_timer_name(n).is_ok()));
+    }
+}
@@ -1,91 +1,381 @@
 use crate::config::DisplayConfig;
+use crate::history::{Session, SessionKind};
+use crate::render::{Progress, Renderer, TimerProgress, describe_in_words};
+use crate::state::{Pomodoro, TimerState};
+use crate::store::Store;
+use crate::timer::Timer;
 use anyhow::Context;
-use chrono::DateTime;
 use chrono::prelude::*;
-use std::fs::{self};
-use std::path::PathBuf;
+use chrono::{DateTime, Duration};
+
+/// How long a pomodoro lasts
+pub const POMODORO_MINS: i64 = 25;
+/// Number of pomodoros in a cycle, after which the pomodoro technique calls for a longer break
+pub const POMODOROS_PER_CYCLE: u32 = 4;
+/// How far in the future a timer's state can be before it's considered a sign of clock skew (eg.
+/// an NTP jump, or a data file synced from a machine with a different clock)
+pub const CLOCK_SKEW_TOLERANCE_SECS: i64 = 60;
+const IDLE_PLACEHOLDER: &str = "{idle}";
+
+/// Starts a pomodoro that started at `started_at` (which can be before `now`, to count time
+/// already spent). A pomodoro or break that was running is recorded in the history log.
+pub fn start_tracking(
+    store: &dyn Store,
+    timer: &Timer,
+    started_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+    task: Option<String>,
+    tags: Vec<String>,
+) -> anyhow::Result<()> {
+    transition(
+        store,
+        timer,
+        now,
+        TimerState::Running(Pomodoro {
+            started_at,
+            task,
+            tags,
+        }),
+    )
+}
+
+/// Starts a break. A pomodoro or break that was running is recorded in the history log.
+pub fn take_break(store: &dyn Store, timer: &Timer, now: DateTime<Utc>) -> anyhow::Result<()> {
+    transition(store, timer, now, TimerState::OnBreak { since: Some(now) })
+}
+
+/// Stops the timer. A pomodoro or break that was running is recorded in the history log.
+pub fn stop_tracking(
+    store: &dyn Store,
+    timer: &Timer,
+    now: DateTime<Utc>,
+) -> Result<(), anyhow::Error> {
+    transition(store, timer, now, TimerState::Stopped { since: Some(now) })
+}
 
-const BREAK_STRING: &str = "break";
-const STOP_STRING: &str = "stop";
+/// Moves a timer to a new state, recording the pomodoro or break that it ends (if any) in the
+/// history log.
+fn transition(
+    store: &dyn Store,
+    timer: &Timer,
+    now: DateTime<Utc>,
+    next: TimerState,
+) -> anyhow::Result<()> {
+    store.update_state(timer, &mut |previous| {
+        if let Some(session) = finished_session(&timer.name, previous, now) {
+            store.append_sessions(&[session])?;
+        }
 
-pub fn start_tracking(file_path: &PathBuf, time: DateTime<Utc>) -> anyhow::Result<()> {
-    fs::write(file_path, time.to_rfc3339()).context("couldn't write to tomo's data file")
+        Ok(next.clone())
+    })
 }
 
-pub fn take_break(file_path: &PathBuf) -> anyhow::Result<()> {
-    fs::write(file_path, BREAK_STRING).context("couldn't write to tomo's data file")
+fn finished_session(timer_name: &str, state: &TimerState, now: DateTime<Utc>) -> Option<Session> {
+    match state {
+        TimerState::Stopped { .. } | TimerState::OnBreak { since: None } => None,
+        TimerState::OnBreak { since: Some(since) } => Some(Session {
+            timer: timer_name.to_string(),
+            kind: SessionKind::Break,
+            start: *since,
+            end: now.max(*since),
+            planned_mins: None,
+            task: None,
+            tags: vec![],
+        }),
+        // a pomodoro is over once it's run for as long as planned, even if it's only ended later
+        TimerState::Running(pomodoro) => Some(Session {
+            timer: timer_name.to_string(),
+            kind: SessionKind::Focus,
+            start: pomodoro.started_at,
+            end: now
+                .min(pomodoro.started_at + Duration::minutes(POMODORO_MINS))
+                .max(pomodoro.started_at),
+            planned_mins: Some(POMODORO_MINS),
+            task: pomodoro.task.clone(),
+            tags: pomodoro.tags.clone(),
+        }),
+    }
 }
 
-pub fn stop_tracking(file_path: &PathBuf) -> Result<(), anyhow::Error> {
-    fs::write(file_path, STOP_STRING).context("couldn't write to tomo's data file")
+/// Works out a timer's progress at `now` from its state.
+pub fn get_progress(
+    store: &dyn Store,
+    timer: &Timer,
+    now: DateTime<Utc>,
+    strict: bool,
+) -> anyhow::Result<Progress> {
+    Ok(match store.read_state(timer, strict)? {
+        TimerState::Stopped { since } => Progress::Stopped {
+            idle_seconds: since.map(|t| now.signed_duration_since(t).num_seconds()),
+        },
+        TimerState::OnBreak { .. } => Progress::OnBreak,
+        TimerState::Running(pomodoro) => Progress::Running {
+            elapsed_seconds: now.signed_duration_since(pomodoro.started_at).num_seconds(),
+            task: pomodoro.task,
+        },
+    })
 }
 
+/// Prints the progress of one or more timers, as rendered by `renderer`. Several timers are shown
+/// in one segment.
 pub fn show_progress(
-    file_path: &PathBuf,
+    store: &dyn Store,
+    timers: &[Timer],
     now: DateTime<Utc>,
     config: &DisplayConfig,
+    renderer: &dyn Renderer,
+    strict: bool,
 ) -> anyhow::Result<()> {
-    let status = fs::read_to_string(file_path).context("couldn't read from tomo's data file")?;
+    let mut progress = vec![];
+    for timer in timers {
+        let timer_progress = get_progress(store, timer, now, strict);
+        let timer_progress = if timers.len() > 1 {
+            timer_progress
+                .with_context(|| format!("couldn't get status of timer \"{}\"", timer.name))?
+        } else {
+            timer_progress?
+        };
 
-    if status == STOP_STRING {
-        return Ok(());
+        progress.push(TimerProgress {
+            timer: timer.name.clone(),
+            progress: timer_progress,
+        });
     }
 
-    if status == BREAK_STRING {
-        print!(
-            "{}{}{}",
-            config.left_pad, config.break_msg, config.right_pad,
-        );
-        return Ok(());
+    if let Some(output) = renderer.render(&progress, config) {
+        print!("{output}");
     }
 
-    let ts_trimmed = status.trim();
+    Ok(())
+}
 
-    let ts = DateTime::parse_from_rfc3339(ts_trimmed)
-        .context("couldn't not parse time from tomo's data file")?;
+/// Lists all timers that aren't stopped, one per line.
+pub fn list_active_timers(
+    store: &dyn Store,
+    timers: &[Timer],
+    now: DateTime<Utc>,
+    config: &DisplayConfig,
+    strict: bool,
+) -> anyhow::Result<()> {
+    let mut active = vec![];
+    for timer in timers {
+        let progress = get_progress(store, timer, now, strict)
+            .with_context(|| format!("couldn't get status of timer \"{}\"", timer.name))?;
+
+        match progress {
+            Progress::Stopped { .. } => {}
+            _ if config.accessible => {
+                active.push((timer.name.as_str(), describe_in_words(&progress)))
+            }
+            Progress::OnBreak => active.push((timer.name.as_str(), config.break_msg.clone())),
+            Progress::Running {
+                elapsed_seconds, ..
+            } => active.push((
+                timer.name.as_str(),
+                get_progress_bar(elapsed_seconds, config),
+            )),
+        }
+    }
 
-    let diff_seconds = now.signed_duration_since(ts.to_utc()).num_seconds();
-    let output = get_progress_bar(diff_seconds, config);
-    println!("{output}");
+    let width = active
+        .iter()
+        .map(|(n, _)| n.len())
+        .max()
+        .unwrap_or_default();
+    for (name, status) in active {
+        println!("{name:<width$}  {}", status.trim());
+    }
 
     Ok(())
 }
 
+/// Returns the message to show when a timer is stopped, if one is configured. `{idle}` in the
+/// message is replaced with how long the timer has been stopped for (or "-" if that's not known).
+pub fn get_idle_msg(idle_seconds: Option<i64>, config: &DisplayConfig) -> Option<String> {
+    if config.idle_msg.is_empty() {
+        return None;
+    }
+
+    let idle = idle_seconds.map_or(String::from("-"), |s| format_duration(s.max(0)));
+    let msg = config.idle_msg.replace(IDLE_PLACEHOLDER, &idle);
+
+    Some(format!("{}{}{}", config.left_pad, msg, config.right_pad))
+}
+
+/// Formats a number of seconds roughly, eg. "42m", "3h 5m", or "2d 2h".
+pub fn format_duration(seconds: i64) -> String {
+    let mins = seconds / 60;
+    match (mins / (24 * 60), (mins / 60) % 24, mins % 60) {
+        (0, 0, m) => format!("{m}m"),
+        (0, h, 0) => format!("{h}h"),
+        (0, h, m) => format!("{h}h {m}m"),
+        (d, 0, _) => format!("{d}d"),
+        (d, h, _) => format!("{d}d {h}h"),
+    }
+}
+
+/// Formats a number of seconds as a clock, eg. "14:05", or "1:02:03" for an hour or more.
+pub fn format_clock(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    match (seconds / 3600, (seconds / 60) % 60, seconds % 60) {
+        (0, m, s) => format!("{m:02}:{s:02}"),
+        (h, m, s) => format!("{h}:{m:02}:{s:02}"),
+    }
+}
+
+/// Returns the progress bar for a pomodoro that started `diff_seconds` ago, or the finished
+/// message once it's done.
 pub fn get_progress_bar(diff_seconds: i64, config: &DisplayConfig) -> String {
-    let chunks = diff_seconds / (25 * 60 / (config.num_blocks as i64));
+    let bar = match progress_bar_parts(diff_seconds, config) {
+        Some((complete, pending)) => complete + &pending,
+        None => config.finished_msg.clone(),
+    };
+
+    format!("{}{}{}", config.left_pad, bar, config.right_pad)
+}
+
+/// Returns the complete and pending parts of a progress bar (without padding), or `None` if the
+/// timer is finished. A negative time (ie, a start time in the future) shows an empty bar.
+pub(crate) fn progress_bar_parts(
+    diff_seconds: i64,
+    config: &DisplayConfig,
+) -> Option<(String, String)> {
+    let chunks = diff_seconds.max(0) / (POMODORO_MINS * 60 / (config.num_blocks as i64));
 
     if chunks >= config.num_blocks as i64 {
-        return format!(
-            "{}{}{}",
-            config.left_pad, config.finished_msg, config.right_pad,
-        );
+        return None;
     }
 
-    let mut bar = String::new();
+    let mut complete = String::new();
 
     for _ in 0..chunks {
-        bar.push_str(&config.complete_block);
+        complete.push_str(&config.complete_block);
         if !config.delimiter.is_empty() {
-            bar.push_str(&config.delimiter);
+            complete.push_str(&config.delimiter);
         }
     }
 
+    let mut pending = String::new();
+
     for _ in 0..((config.num_blocks as i64) - chunks - 1) {
-        bar.push_str(&config.pending_block);
+        pending.push_str(&config.pending_block);
         if !config.delimiter.is_empty() {
-            bar.push_str(&config.delimiter);
+            pending.push_str(&config.delimiter);
         }
     }
 
-    bar.push_str(&config.pending_block);
+    pending.push_str(&config.pending_block);
 
-    format!("{}{}{}", config.left_pad, bar, config.right_pad,)
+    Some((complete, pending))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::store::MemoryStore;
     use insta::assert_snapshot;
+    use std::path::Path;
+
+    fn time(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts)
+            .expect("timestamp should've been parsed")
+            .to_utc()
+    }
+
+    #[test]
+    fn stopping_a_pomodoro_records_it() {
+        // GIVEN
+        let store = MemoryStore::new();
+        let timer = Timer::new(Path::new(".tomo"), None).expect("timer should've been created");
+        let started_at = time("2025-01-06T09:00:00Z");
+        let stopped_at = time("2025-01-06T09:10:00Z");
+        start_tracking(
+            &store,
+            &timer,
+            started_at,
+            started_at,
+            Some(String::from("JIRA-123")),
+            vec![],
+        )
+        .expect("tracking should've started");
+
+        // WHEN
+        stop_tracking(&store, &timer, stopped_at).expect("tracking should've stopped");
+
+        // THEN
+        let state = store
+            .read_state(&timer, true)
+            .expect("state should've been read");
+        assert_eq!(
+            state,
+            TimerState::Stopped {
+                since: Some(stopped_at)
+            }
+        );
+        let sessions = store.read_sessions().expect("sessions should've been read");
+        assert_eq!(
+            sessions,
+            vec![Session {
+                timer: String::from("default"),
+                kind: SessionKind::Focus,
+                start: started_at,
+                end: stopped_at,
+                planned_mins: Some(POMODORO_MINS),
+                task: Some(String::from("JIRA-123")),
+                tags: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn pomodoros_are_recorded_up_to_their_planned_length() {
+        // GIVEN
+        let store = MemoryStore::new();
+        let timer = Timer::new(Path::new(".tomo"), None).expect("timer should've been created");
+        let started_at = time("2025-01-06T09:00:00Z");
+        start_tracking(&store, &timer, started_at, started_at, None, vec![])
+            .expect("tracking should've started");
+
+        // WHEN
+        take_break(&store, &timer, time("2025-01-06T09:40:00Z")).expect("break should've started");
+        stop_tracking(&store, &timer, time("2025-01-06T09:45:00Z"))
+            .expect("tracking should've stopped");
+
+        // THEN
+        let sessions = store.read_sessions().expect("sessions should've been read");
+        let got = sessions
+            .iter()
+            .map(|s| (s.kind, s.start, s.end))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            got,
+            vec![
+                (SessionKind::Focus, started_at, time("2025-01-06T09:25:00Z")),
+                (
+                    SessionKind::Break,
+                    time("2025-01-06T09:40:00Z"),
+                    time("2025-01-06T09:45:00Z")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn timers_without_a_state_are_stopped_unless_strict() {
+        // GIVEN
+        let store = MemoryStore::new();
+        let timer =
+            Timer::new(Path::new(".tomo"), Some("pairing")).expect("timer should've been created");
+
+        // WHEN
+        let got = get_progress(&store, &timer, time("2025-01-06T09:00:00Z"), false)
+            .expect("progress should've been read");
+        let got_strict = get_progress(&store, &timer, time("2025-01-06T09:00:00Z"), true);
+
+        // THEN
+        assert_eq!(got, Progress::Stopped { idle_seconds: None });
+        assert!(got_strict.is_err());
+    }
 
     #[test]
     fn get_progress_works_with_defaults() {
@@ -179,6 +469,18 @@ mod tests {
         insta::assert_snapshot!(got_at_min_one, @" ▫▫▫▫▫▫▫▫▫▫");
     }
 
+    #[test]
+    fn get_progress_shows_empty_progress_bar_for_start_times_in_the_future() {
+        // GIVEN
+        let config = DisplayConfig::default();
+
+        // WHEN
+        let got = get_progress_bar(-10 * 60, &config);
+
+        // THEN
+        insta::assert_snapshot!(got, @" ▫▫▫▫▫▫▫▫▫▫");
+    }
+
     #[test]
     fn get_progress_shows_finished_msg_when_timer_is_finished() {
         // GIVEN
@@ -198,4 +500,48 @@ mod tests {
         insta::assert_snapshot!(got_at_min_25, @"[[fertig]]");
         insta::assert_snapshot!(got_at_min_26, @"[[fertig]]");
     }
+
+    #[test]
+    fn get_idle_msg_is_empty_by_default() {
+        // GIVEN
+        let config = DisplayConfig::default();
+
+        // WHEN
+        let got = get_idle_msg(Some(42 * 60), &config);
+
+        // THEN
+        assert!(got.is_none());
+    }
+
+    #[test]
+    fn get_idle_msg_shows_idle_time() {
+        // GIVEN
+        let default_config = DisplayConfig::default();
+        let config = DisplayConfig {
+            idle_msg: String::from("idle for {idle}"),
+            ..default_config
+        };
+
+        // WHEN
+        let got_mins = get_idle_msg(Some(42 * 60 + 59), &config);
+        let got_hours = get_idle_msg(Some(3 * 60 * 60 + 5 * 60), &config);
+        let got_days = get_idle_msg(Some(50 * 60 * 60), &config);
+        let got_unknown = get_idle_msg(None, &config);
+
+        // THEN
+        insta::assert_snapshot!(got_mins.unwrap_or_default(), @" idle for 42m ");
+        insta::assert_snapshot!(got_hours.unwrap_or_default(), @" idle for 3h 5m ");
+        insta::assert_snapshot!(got_days.unwrap_or_default(), @" idle for 2d 2h ");
+        insta::assert_snapshot!(got_unknown.unwrap_or_default(), @" idle for - ");
+    }
+
+    #[test]
+    fn clock_is_formatted_with_hours_only_when_needed() {
+        // GIVEN
+        // WHEN
+        let got = [0, 65, 25 * 60, 3723].map(format_clock);
+
+        // THEN
+        assert_eq!(got, ["00:00", "01:05", "25:00", "1:02:03"]);
+    }
 }
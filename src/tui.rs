@@ -0,0 +1,321 @@
+use chrono::{DateTime, Utc};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, queue, style, terminal};
+use std::io::{self, Write};
+use std::time::Duration;
+use tomo::config::{Config, DisplayConfig};
+use tomo::goals::{local_date, pomodoros_per_day};
+use tomo::history::{SessionKind, SessionQuery, TimeBound};
+use tomo::state::TimerState;
+use tomo::store::Store;
+use tomo::time::TimeSource;
+use tomo::timer::{DEFAULT_TIMER_NAME, Timer};
+use tomo::track::{
+    POMODORO_MINS, POMODOROS_PER_CYCLE, format_clock, get_progress_bar, start_tracking,
+    stop_tracking, take_break,
+};
+
+const REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_BAR_BLOCKS: u16 = 60;
+const MIN_BAR_BLOCKS: u16 = 3;
+const KEY_BINDINGS: &str = "[s] start  [b] break  [x] stop  [q] quit";
+
+/// Everything shown on the TUI's screen at a point in time.
+struct Screen {
+    timer_name: String,
+    state: TimerState,
+    now: DateTime<Utc>,
+    completed_today: u32,
+    daily_goal: Option<u32>,
+}
+
+/// Runs a full-screen view of a timer until the user quits. The timer's state is re-read from its
+/// data file on every redraw, so the view stays in sync with changes made by other invocations
+/// of tomo (and vice versa).
+pub fn run_tui(
+    store: &dyn Store,
+    timer: &Timer,
+    display_config: &DisplayConfig,
+    config: &Config,
+    time: &TimeSource,
+) -> anyhow::Result<()> {
+    full_screen(|stdout| event_loop(stdout, store, timer, display_config, config, time))
+}
+
+/// Runs `f` on the terminal's alternate screen, in raw mode, restoring the terminal afterwards.
+pub fn full_screen<F>(f: F) -> anyhow::Result<()>
+where
+    F: FnOnce(&mut io::Stdout) -> anyhow::Result<()>,
+{
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    let _restore = RestoreTerminal;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    f(&mut stdout)
+}
+
+/// Restores the terminal when dropped, so that it's restored even if drawing failed or panicked.
+struct RestoreTerminal;
+
+impl Drop for RestoreTerminal {
+    fn drop(&mut self) {
+        // there's nothing left to do if restoring fails, and a panic here would abort
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub fn is_quit_key(key: &KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => true,
+        KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+        _ => false,
+    }
+}
+
+fn event_loop(
+    stdout: &mut io::Stdout,
+    store: &dyn Store,
+    timer: &Timer,
+    display_config: &DisplayConfig,
+    config: &Config,
+    time: &TimeSource,
+) -> anyhow::Result<()> {
+    loop {
+        let now = time.now();
+        let screen = load_screen(store, timer, now, config)?;
+        let (width, height) = terminal::size()?;
+        draw_centered(
+            stdout,
+            &screen_lines(&screen, display_config, width),
+            width,
+            height,
+        )?;
+
+        if !event::poll(REDRAW_INTERVAL)? {
+            continue;
+        }
+
+        let Event::Key(key) = event::read()? else {
+            // resizes (and other events) just lead to a redraw
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        if is_quit_key(&key) {
+            return Ok(());
+        }
+
+        let now = time.now();
+        match key.code {
+            KeyCode::Char('s') => start_tracking(store, timer, now, now, None, vec![])?,
+            KeyCode::Char('b') => take_break(store, timer, now)?,
+            KeyCode::Char('x') => stop_tracking(store, timer, now)?,
+            _ => {}
+        }
+    }
+}
+
+fn load_screen(
+    store: &dyn Store,
+    timer: &Timer,
+    now: DateTime<Utc>,
+    config: &Config,
+) -> anyhow::Result<Screen> {
+    let state = store.read_state(timer, false)?;
+    let today = local_date(&now);
+    let per_day = pomodoros_per_day(&store.query_sessions(&SessionQuery {
+        kind: Some(SessionKind::Focus),
+        ..SessionQuery::between(Some(&TimeBound::Date(today)), None)
+    })?);
+
+    Ok(Screen {
+        timer_name: timer.name.clone(),
+        state,
+        now,
+        completed_today: per_day.get(&today).copied().unwrap_or_default(),
+        daily_goal: config.daily_goal,
+    })
+}
+
+/// Clears the screen and draws lines in the middle of it.
+pub fn draw_centered(
+    stdout: &mut io::Stdout,
+    lines: &[String],
+    width: u16,
+    height: u16,
+) -> io::Result<()> {
+    queue!(stdout, terminal::Clear(terminal::ClearType::All))?;
+
+    let top = height.saturating_sub(lines.len() as u16) / 2;
+    for (i, line) in lines.iter().enumerate() {
+        let line_width = line.chars().count() as u16;
+        let left = width.saturating_sub(line_width) / 2;
+        queue!(
+            stdout,
+            cursor::MoveTo(left, top + i as u16),
+            style::Print(line)
+        )?;
+    }
+
+    stdout.flush()
+}
+
+fn screen_lines(screen: &Screen, display_config: &DisplayConfig, width: u16) -> Vec<String> {
+    let bar_config = DisplayConfig {
+        left_pad: String::new(),
+        right_pad: String::new(),
+        num_blocks: bar_blocks(width, display_config) as u8,
+        ..display_config.clone()
+    };
+
+    let title = if screen.timer_name == DEFAULT_TIMER_NAME {
+        String::from("tomo")
+    } else {
+        format!("tomo · {}", screen.timer_name)
+    };
+
+    let (bar, status, task) = match &screen.state {
+        TimerState::Running(pomodoro) => {
+            let elapsed = screen
+                .now
+                .signed_duration_since(pomodoro.started_at)
+                .num_seconds()
+                .max(0);
+            let remaining = POMODORO_MINS * 60 - elapsed;
+            let status = if remaining > 0 {
+                format!("{} left", format_clock(remaining))
+            } else {
+                String::from("done")
+            };
+            let task = pomodoro
+                .task
+                .iter()
+                .cloned()
+                .chain(pomodoro.tags.iter().map(|t| format!("#{t}")))
+                .collect::<Vec<_>>()
+                .join("  ");
+            (get_progress_bar(elapsed, &bar_config), status, task)
+        }
+        TimerState::OnBreak { since } => {
+            let status = match since {
+                Some(since) => format!(
+                    "on a break for {}",
+                    format_clock(screen.now.signed_duration_since(*since).num_seconds())
+                ),
+                None => String::from("on a break"),
+            };
+            (display_config.break_msg.clone(), status, String::new())
+        }
+        TimerState::Stopped { .. } => (
+            get_progress_bar(0, &bar_config),
+            String::from("stopped"),
+            String::new(),
+        ),
+    };
+
+    let goal = screen.daily_goal.map_or(String::new(), |g| format!("/{g}"));
+    let counts = format!(
+        "pomodoro {} of {POMODOROS_PER_CYCLE} · today {}{goal}",
+        screen.completed_today % POMODOROS_PER_CYCLE + 1,
+        screen.completed_today,
+    );
+
+    vec![
+        title,
+        String::new(),
+        bar,
+        String::new(),
+        status,
+        task,
+        String::new(),
+        counts,
+        String::new(),
+        String::from(KEY_BINDINGS),
+    ]
+}
+
+/// Returns the number of blocks in the progress bar, so that it fills most of the terminal's
+/// width.
+pub fn bar_blocks(width: u16, display_config: &DisplayConfig) -> u16 {
+    let block_width = (display_config.complete_block.chars().count()
+        + display_config.delimiter.chars().count())
+    .max(1) as u16;
+
+    (width.saturating_sub(8) / block_width).clamp(MIN_BAR_BLOCKS, MAX_BAR_BLOCKS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tomo::state::Pomodoro;
+
+    fn time(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts)
+            .expect("timestamp should've been parsed")
+            .to_utc()
+    }
+
+    #[test]
+    fn screen_shows_a_running_pomodoro() {
+        // GIVEN
+        let started_at = time("2025-01-06T09:00:00Z");
+        let screen = Screen {
+            timer_name: String::from("default"),
+            state: TimerState::Running(Pomodoro {
+                started_at,
+                task: Some(String::from("JIRA-123")),
+                tags: vec![String::from("backend")],
+            }),
+            now: started_at + Duration::seconds(10 * 60 + 30),
+            completed_today: 5,
+            daily_goal: Some(8),
+        };
+
+        // WHEN
+        let got = screen_lines(&screen, &DisplayConfig::default(), 18);
+
+        // THEN
+        assert_eq!(
+            got,
+            vec![
+                "tomo",
+                "",
+                "▪▪▪▪▫▫▫▫▫▫",
+                "",
+                "14:30 left",
+                "JIRA-123  #backend",
+                "",
+                "pomodoro 2 of 4 · today 5/8",
+                "",
+                KEY_BINDINGS,
+            ]
+        );
+    }
+
+    #[test]
+    fn screen_shows_a_break() {
+        // GIVEN
+        let since = time("2025-01-06T09:25:00Z");
+        let screen = Screen {
+            timer_name: String::from("pairing"),
+            state: TimerState::OnBreak { since: Some(since) },
+            now: since + Duration::seconds(65),
+            completed_today: 4,
+            daily_goal: None,
+        };
+
+        // WHEN
+        let got = screen_lines(&screen, &DisplayConfig::default(), 80);
+
+        // THEN
+        assert_eq!(got[0], "tomo · pairing");
+        assert_eq!(got[2], "\\o/");
+        assert_eq!(got[4], "on a break for 01:05");
+        assert_eq!(got[7], "pomodoro 1 of 4 · today 4");
+    }
+}
@@ -15,34 +15,58 @@ fn shows_help() {
     let mut cmd = fx.cmd(["--help"]);
 
     // THEN
-    assert_cmd_snapshot!(cmd, @r#"
-    success: true
-    exit_code: 0
-    ----- stdout -----
-    tomo is a no-frills pomodoro progress indicator for tmux
-
-    Usage: tomo [OPTIONS] [COMMAND]
-
-    Commands:
-      start  Start a pomodoro timer
-      stop   Stop timer
-      break  Start a break
-      help   Print this message or the help of the given subcommand(s)
-
-    Options:
-      -p, --pending-block <STRING>   String to represent a "pending" block in the progress bar [default: ▫]
-      -c, --complete-block <STRING>  String to represent a "complete" block in the progress bar [default: ▪]
-      -l, --left-pad <STRING>        String to pad the output with on the LHS [default: " "]
-      -r, --right-pad <STRING>       String to pad the output with on the RHS [default: " "]
-      -d, --delimiter <STRING>       Delimiter between progress bar chunks [default: ""]
-      -n, --num-blocks <NUM>         Number of blocks to show in progress bar [default: 10]
-          --finished-msg <STRING>    Message to show when timer is finished [default: done]
-          --break-msg <STRING>       Message to show when on a break [default: \o/]
-          --data-file <STRING>       tomo's data file (defaults to <YOUR_DATA_DIR>/tomo/.tomo)
-      -h, --help                     Print help
-
-    ----- stderr -----
-    "#);
+    // the sqlite feature adds a subcommand, which is left out so that one snapshot covers both builds
+    insta::with_settings!({filters => vec![
+        (r"\n  migrate +[^\n]*", ""),
+    ]}, {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        tomo is a no-frills pomodoro progress indicator for tmux
+
+        Usage: tomo [OPTIONS] [COMMAND]
+
+        Commands:
+          start        Start a pomodoro timer
+          stop         Stop timer
+          break        Start a break
+          list         List all active timers
+          status       Show the timer's state, times, task, and today's count, one per line
+          completions  Print the script that sets up shell completions for tomo
+          man          Print tomo's man page
+          export       Export recorded pomodoros and breaks
+          import       Import pomodoros and breaks from another app, or from tomo's own export
+          stats        Show progress towards your goals, and how often you've met them
+          report       Report on recorded pomodoros
+          tui          Show a full-screen view of the timer, with key bindings to start, break, and stop it
+          clock        Show the time left in big digits, with the progress bar underneath
+          doctor       Check tomo's setup for problems (eg. timestamps in the future, after a clock jump)
+          help         Print this message or the help of the given subcommand(s)
+
+        Options:
+          -p, --pending-block <STRING>   String to represent a "pending" block in the progress bar [default: ▫]
+          -c, --complete-block <STRING>  String to represent a "complete" block in the progress bar [default: ▪]
+          -l, --left-pad <STRING>        String to pad the output with on the LHS [default: " "]
+          -r, --right-pad <STRING>       String to pad the output with on the RHS [default: " "]
+          -d, --delimiter <STRING>       Delimiter between progress bar chunks [default: ""]
+          -n, --num-blocks <NUM>         Number of blocks to show in progress bar [default: 10]
+              --finished-msg <STRING>    Message to show when timer is finished [default: done]
+              --break-msg <STRING>       Message to show when on a break [default: \o/]
+              --idle-msg <STRING>        Message to show when no timer is running (defaults to the config file's idle_msg); "{idle}" is replaced with how long it's been stopped
+              --data-file <STRING>       tomo's data file (defaults to <YOUR_DATA_DIR>/tomo/.tomo) [env: TOMO_DATA_FILE=]
+              --config-file <STRING>     tomo's config file (defaults to <YOUR_CONFIG_DIR>/tomo/config.toml) [env: TOMO_CONFIG_FILE=]
+          -t, --timer <NAME>             Named timer to act on; can be repeated to show several timers in one segment
+              --timer-sep <STRING>       Separator between timers when showing several of them [default: " | "]
+          -o, --output <FORMAT>          How to show the timer's progress [default: plain] [possible values: plain, tmux, ansi, json, waybar]
+              --accessible               Describe progress in words (eg. "focus, 14 minutes left") rather than with a progress bar [env: TOMO_ACCESSIBLE=]
+              --tmux-scope               Scope the timer to the current tmux session (falls back to the global timer outside tmux) [env: TOMO_TMUX_SCOPE=]
+              --strict                   Fail if tomo's data file is missing or corrupt, instead of treating the timer as stopped
+          -h, --help                     Print help
+
+        ----- stderr -----
+        "#);
+    });
 }
 
 #[test]
@@ -265,10 +289,98 @@ fn using_a_custom_break_message_works() {
     ");
 }
 
+#[test]
+fn corrupt_data_file_is_treated_as_stopped_with_a_one_time_warning() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_data_file("2025-01-01T10:3");
+
+    // WHEN
+    // THEN
+    let mut show_cmd = fx.base_cmd();
+    assert_cmd_snapshot!(show_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    warning: tomo's data file is corrupt, treating timer as stopped: couldn't not parse time from tomo's data file: premature end of input
+    ");
+
+    let mut show_again_cmd = fx.base_cmd();
+    assert_cmd_snapshot!(show_again_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn missing_data_file_is_treated_as_stopped() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    // THEN
+    let mut show_cmd = fx.base_cmd();
+    assert_cmd_snapshot!(show_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+}
+
 //------------//
 //  FAILURES  //
 //------------//
 
+#[test]
+fn fails_if_data_file_is_missing_in_strict_mode() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    // THEN
+    let mut show_cmd = fx.cmd(["--strict"]);
+    assert_cmd_snapshot!(show_cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't read from tomo's data file
+
+    Caused by:
+        No such file or directory (os error 2)
+    ");
+}
+
+#[test]
+fn fails_if_data_file_is_corrupt_in_strict_mode() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_data_file("2025-01-01T10:3");
+
+    // WHEN
+    // THEN
+    let mut show_cmd = fx.cmd(["--strict"]);
+    assert_cmd_snapshot!(show_cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't not parse time from tomo's data file
+
+    Caused by:
+        premature end of input
+    ");
+}
+
 #[test]
 fn fails_if_num_blocks_is_greater_than_threshold() {
     // GIVEN
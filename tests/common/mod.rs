@@ -6,6 +6,7 @@ pub struct Fixture {
     _bin_path: PathBuf,
     _temp_dir: TempDir,
     data_file_path: String,
+    config_file_path: String,
 }
 
 #[cfg(test)]
@@ -20,20 +21,56 @@ impl Fixture {
             .to_str()
             .expect("temporary directory path is not valid utf-8")
             .to_string();
+        let config_file_path = temp_dir
+            .path()
+            .join("config.toml")
+            .to_str()
+            .expect("temporary directory path is not valid utf-8")
+            .to_string();
 
         Self {
             _bin_path: bin_path,
             _temp_dir: temp_dir,
             data_file_path,
+            config_file_path,
         }
     }
 
     pub fn base_cmd(&self) -> Command {
         let mut cmd = Command::new(&self._bin_path);
         cmd.args(["--data-file", &self.data_file_path]);
+        cmd.args(["--config-file", &self.config_file_path]);
         cmd
     }
 
+    pub fn write_data_file(&self, contents: &str) {
+        std::fs::write(&self.data_file_path, contents).expect("data file should've been written");
+    }
+
+    pub fn write_history_file(&self, contents: &str) {
+        std::fs::write(format!("{}.history", self.data_file_path), contents)
+            .expect("history file should've been written");
+    }
+
+    pub fn write_config_file(&self, contents: &str) {
+        std::fs::write(&self.config_file_path, contents)
+            .expect("config file should've been written");
+    }
+
+    pub fn read_history_file(&self) -> String {
+        std::fs::read_to_string(format!("{}.history", self.data_file_path))
+            .expect("history file should've been read")
+    }
+
+    /// Writes a file next to the data file, and returns its path
+    pub fn write_file(&self, name: &str, contents: &str) -> String {
+        let path = self._temp_dir.path().join(name);
+        std::fs::write(&path, contents).expect("file should've been written");
+        path.to_str()
+            .expect("temporary directory path is not valid utf-8")
+            .to_string()
+    }
+
     pub fn cmd<I, S>(&self, args: I) -> Command
     where
         I: IntoIterator<Item = S>,
@@ -42,6 +79,20 @@ impl Fixture {
         let mut command = Command::new(&self._bin_path);
         command.args(args);
         command.args(["--data-file", &self.data_file_path]);
+        command.args(["--config-file", &self.config_file_path]);
+        command
+    }
+
+    /// Like `cmd`, but passes the data file via the environment rather than a flag
+    pub fn env_cmd<I, S>(&self, args: I) -> Command
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut command = Command::new(&self._bin_path);
+        command.args(args);
+        command.env("TOMO_DATA_FILE", &self.data_file_path);
+        command.env("TOMO_CONFIG_FILE", &self.config_file_path);
         command
     }
 }
@@ -0,0 +1,124 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn generating_completions_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["completions", "fish"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    complete --keep-order --exclusive --command tomo --arguments "(COMPLETE=fish tomo -- (commandline --current-process --tokenize --cut-at-cursor) (commandline --current-token))"
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn timer_names_are_completed_dynamically() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut start_cmd = fx.cmd(["start", "--timer", "pairing"]);
+    assert_cmd_snapshot!(start_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+    let mut break_cmd = fx.cmd(["break", "--timer", "personal"]);
+    assert_cmd_snapshot!(break_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+    let mut stop_cmd = fx.cmd(["stop", "--timer", "work"]);
+    assert_cmd_snapshot!(stop_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+
+    // WHEN
+    // THEN
+    let mut complete_cmd = fx.env_cmd(["--", "tomo", "--timer", "p"]);
+    complete_cmd.env("COMPLETE", "fish");
+    assert_cmd_snapshot!(complete_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    pairing
+    personal
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn task_labels_are_completed_from_the_history() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(
+        r#"{"timer":"default","kind":"focus","start":"2025-01-06T09:00:00Z","end":"2025-01-06T09:25:00Z","planned_mins":25,"task":"JIRA-123"}
+{"timer":"default","kind":"focus","start":"2025-01-06T10:00:00Z","end":"2025-01-06T10:25:00Z","planned_mins":25,"task":"JIRA-130"}
+{"timer":"default","kind":"focus","start":"2025-01-06T11:00:00Z","end":"2025-01-06T11:25:00Z","planned_mins":25,"task":"JIRA-123"}
+{"timer":"default","kind":"focus","start":"2025-01-06T12:00:00Z","end":"2025-01-06T12:25:00Z","planned_mins":25,"task":"docs"}
+"#,
+    );
+
+    // WHEN
+    // THEN
+    let mut complete_cmd = fx.env_cmd(["--", "tomo", "start", "--task", "JIRA"]);
+    complete_cmd.env("COMPLETE", "fish");
+    assert_cmd_snapshot!(complete_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    JIRA-123
+    JIRA-130
+
+    ----- stderr -----
+    ");
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn generating_completions_fails_for_unknown_shell() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["completions", "tcsh"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'tcsh' for '<SHELL>'
+      [possible values: bash, elvish, fish, powershell, zsh]
+
+      tip: a similar value exists: 'zsh'
+
+    For more information, try '--help'.
+    ");
+}
@@ -0,0 +1,218 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+const FILTERS: [(&str, &str); 2] = [
+    (r"\S*/(\.tomo|config\.toml)", "[TEMP_DIR]/$1"),
+    (r"mode \d+", "mode [MODE]"),
+];
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn doctor_finds_nothing_wrong_with_a_fresh_setup() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    let mut cmd = fx.cmd(["doctor", "--now", "2025-01-06T09:10:00Z"]);
+    cmd.env_remove("TMUX");
+
+    // THEN
+    insta::with_settings!({filters => FILTERS.to_vec()}, {
+        assert_cmd_snapshot!(cmd, @r"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        ok       data file  [TEMP_DIR]/.tomo (set via --data-file or TOMO_DATA_FILE); doesn't exist yet, it's written once a timer is started
+        ok       state      no timer has a state yet
+        ok       history    0 session(s) recorded
+        ok       config     [TEMP_DIR]/config.toml (set via --config-file or TOMO_CONFIG_FILE); doesn't exist, so the defaults are used
+        ok       lock       not held by any tomo process
+        ok       tmux       not running inside tmux
+        ok       clock      nothing is in the future (it's 2025-01-06T09:10:00+00:00)
+
+        ----- stderr -----
+        ");
+    });
+}
+
+#[test]
+fn doctor_reports_timers_states() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.cmd(["start", "--task", "JIRA-123"])
+        .args(["--now", "2025-01-06T09:00:00Z"])
+        .output()
+        .expect("command should've run");
+    fx.cmd(["break", "--timer", "pairing"])
+        .args(["--now", "2025-01-06T09:05:00Z"])
+        .output()
+        .expect("command should've run");
+    fx.write_config_file("daily_goal = 8\n");
+
+    // WHEN
+    let mut cmd = fx.cmd(["doctor", "--now", "2025-01-06T09:10:00Z"]);
+    cmd.env_remove("TMUX");
+
+    // THEN
+    insta::with_settings!({filters => FILTERS.to_vec()}, {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        ok       data file  [TEMP_DIR]/.tomo (set via --data-file or TOMO_DATA_FILE); readable and writable (mode [MODE])
+        ok       state      default: running since 2025-01-06T09:00:00+00:00, on "JIRA-123"; pairing: on a break since 2025-01-06T09:05:00+00:00
+        ok       history    0 session(s) recorded
+        ok       config     [TEMP_DIR]/config.toml (set via --config-file or TOMO_CONFIG_FILE); parsed
+        ok       lock       not held by any tomo process
+        ok       tmux       not running inside tmux
+        ok       clock      nothing is in the future (it's 2025-01-06T09:10:00+00:00)
+
+        ----- stderr -----
+        "#);
+    });
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn doctor_flags_start_times_in_the_future() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.cmd(["start", "--now", "2025-01-06T11:05:00Z"])
+        .output()
+        .expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd(["doctor", "--now", "2025-01-06T09:00:00Z"]);
+    cmd.env_remove("TMUX");
+
+    // THEN
+    insta::with_settings!({filters => FILTERS.to_vec()}, {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: false
+        exit_code: 1
+        ----- stdout -----
+        ok       data file  [TEMP_DIR]/.tomo (set via --data-file or TOMO_DATA_FILE); readable and writable (mode [MODE])
+        ok       state      default: running since 2025-01-06T11:05:00+00:00
+        ok       history    0 session(s) recorded
+        ok       config     [TEMP_DIR]/config.toml (set via --config-file or TOMO_CONFIG_FILE); doesn't exist, so the defaults are used
+        ok       lock       not held by any tomo process
+        ok       tmux       not running inside tmux
+        problem  clock      timer "default" started 2h 5m in the future; check this machine's clock (it's 2025-01-06T09:00:00+00:00), and the clocks of machines the data file is synced from
+
+        ----- stderr -----
+        Error: tomo doctor found 1 problem(s)
+        "#);
+    });
+}
+
+#[test]
+fn doctor_flags_history_in_the_future() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(
+        r#"{"timer":"default","kind":"focus","start":"2025-01-06T09:00:00Z","end":"2025-01-06T09:25:00Z","planned_mins":25}
+{"timer":"default","kind":"focus","start":"2025-01-07T09:00:00Z","end":"2025-01-07T09:25:00Z","planned_mins":25}
+"#,
+    );
+
+    // WHEN
+    let mut cmd = fx.cmd(["doctor", "--now", "2025-01-06T10:00:00Z"]);
+    cmd.env_remove("TMUX");
+
+    // THEN
+    insta::with_settings!({filters => FILTERS.to_vec()}, {
+        assert_cmd_snapshot!(cmd, @r"
+        success: false
+        exit_code: 1
+        ----- stdout -----
+        ok       data file  [TEMP_DIR]/.tomo (set via --data-file or TOMO_DATA_FILE); doesn't exist yet, it's written once a timer is started
+        ok       state      no timer has a state yet
+        ok       history    2 session(s) recorded
+        ok       config     [TEMP_DIR]/config.toml (set via --config-file or TOMO_CONFIG_FILE); doesn't exist, so the defaults are used
+        ok       lock       not held by any tomo process
+        ok       tmux       not running inside tmux
+        problem  clock      1 session(s) in the history log end in the future (the latest in 23h 25m); check this machine's clock (it's 2025-01-06T10:00:00+00:00), and the clocks of machines the data file is synced from
+
+        ----- stderr -----
+        Error: tomo doctor found 1 problem(s)
+        ");
+    });
+}
+
+#[test]
+fn doctor_flags_corrupt_data_files_without_resetting_them() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_data_file("not a timestamp");
+
+    // WHEN
+    let mut cmd = fx.cmd(["doctor", "--now", "2025-01-06T09:00:00Z"]);
+    cmd.env_remove("TMUX");
+
+    // THEN
+    insta::with_settings!({filters => FILTERS.to_vec()}, {
+        assert_cmd_snapshot!(cmd, @r"
+        success: false
+        exit_code: 1
+        ----- stdout -----
+        ok       data file  [TEMP_DIR]/.tomo (set via --data-file or TOMO_DATA_FILE); readable and writable (mode [MODE])
+        problem  state      default: couldn't be parsed (couldn't not parse time from tomo's data file: premature end of input)
+        ok       history    0 session(s) recorded
+        ok       config     [TEMP_DIR]/config.toml (set via --config-file or TOMO_CONFIG_FILE); doesn't exist, so the defaults are used
+        ok       lock       not held by any tomo process
+        ok       tmux       not running inside tmux
+        ok       clock      nothing is in the future (it's 2025-01-06T09:00:00+00:00)
+
+        ----- stderr -----
+        Error: tomo doctor found 1 problem(s)
+        ");
+    });
+    let output = fx
+        .cmd(["--strict"])
+        .output()
+        .expect("command should've run");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn doctor_flags_config_files_that_cant_be_parsed() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file("daily_goals = 8\n");
+
+    // WHEN
+    let mut cmd = fx.cmd(["doctor", "--now", "2025-01-06T09:00:00Z"]);
+    cmd.env_remove("TMUX");
+
+    // THEN
+    insta::with_settings!({filters => FILTERS.to_vec()}, {
+        assert_cmd_snapshot!(cmd, @r"
+        success: false
+        exit_code: 1
+        ----- stdout -----
+        ok       data file  [TEMP_DIR]/.tomo (set via --data-file or TOMO_DATA_FILE); doesn't exist yet, it's written once a timer is started
+        ok       state      no timer has a state yet
+        ok       history    0 session(s) recorded
+        problem  config     [TEMP_DIR]/config.toml (set via --config-file or TOMO_CONFIG_FILE); couldn't parse tomo's config file: TOML parse error at line 1, column 1
+          |
+        1 | daily_goals = 8
+          | ^^^^^^^^^^^
+        unknown field `daily_goals`, expected one of `daily_goal`, `weekly_goal`, `idle_msg`, `accessible`
+
+        ok       lock       not held by any tomo process
+        ok       tmux       not running inside tmux
+        ok       clock      nothing is in the future (it's 2025-01-06T09:00:00+00:00)
+
+        ----- stderr -----
+        Error: tomo doctor found 1 problem(s)
+        ");
+    });
+}
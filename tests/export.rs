@@ -0,0 +1,372 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+const HISTORY: &str = r#"{"timer":"default","kind":"focus","start":"2025-01-06T09:00:00Z","end":"2025-01-06T09:25:00Z","planned_mins":25,"task":"JIRA-123","tags":["backend","review"]}
+{"timer":"default","kind":"break","start":"2025-01-06T09:25:00Z","end":"2025-01-06T09:30:00Z"}
+{"timer":"pairing","kind":"focus","start":"2025-01-07T14:00:00Z","end":"2025-01-07T14:12:30Z","planned_mins":25,"task":"write docs, again"}
+"#;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn exporting_to_csv_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut cmd = fx.cmd(["export", "--format", "csv"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    start,end,kind,timer,planned_mins,actual_mins,completed,task,tags
+    2025-01-06T09:00:00+00:00,2025-01-06T09:25:00+00:00,focus,default,25,25,true,JIRA-123,"backend,review"
+    2025-01-06T09:25:00+00:00,2025-01-06T09:30:00+00:00,break,default,,5,true,,
+    2025-01-07T14:00:00+00:00,2025-01-07T14:12:30+00:00,focus,pairing,25,12,false,"write docs, again",
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn exporting_to_json_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut cmd = fx.cmd(["export", "--format", "json"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [
+      {
+        "start": "2025-01-06T09:00:00+00:00",
+        "end": "2025-01-06T09:25:00+00:00",
+        "kind": "focus",
+        "timer": "default",
+        "planned_mins": 25,
+        "actual_mins": 25,
+        "completed": true,
+        "task": "JIRA-123",
+        "tags": [
+          "backend",
+          "review"
+        ]
+      },
+      {
+        "start": "2025-01-06T09:25:00+00:00",
+        "end": "2025-01-06T09:30:00+00:00",
+        "kind": "break",
+        "timer": "default",
+        "planned_mins": null,
+        "actual_mins": 5,
+        "completed": true,
+        "task": null,
+        "tags": []
+      },
+      {
+        "start": "2025-01-07T14:00:00+00:00",
+        "end": "2025-01-07T14:12:30+00:00",
+        "kind": "focus",
+        "timer": "pairing",
+        "planned_mins": 25,
+        "actual_mins": 12,
+        "completed": false,
+        "task": "write docs, again",
+        "tags": []
+      }
+    ]
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn exporting_to_jsonl_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut cmd = fx.cmd(["export", "--format", "jsonl"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {"start":"2025-01-06T09:00:00+00:00","end":"2025-01-06T09:25:00+00:00","kind":"focus","timer":"default","planned_mins":25,"actual_mins":25,"completed":true,"task":"JIRA-123","tags":["backend","review"]}
+    {"start":"2025-01-06T09:25:00+00:00","end":"2025-01-06T09:30:00+00:00","kind":"break","timer":"default","planned_mins":null,"actual_mins":5,"completed":true,"task":null,"tags":[]}
+    {"start":"2025-01-07T14:00:00+00:00","end":"2025-01-07T14:12:30+00:00","kind":"focus","timer":"pairing","planned_mins":25,"actual_mins":12,"completed":false,"task":"write docs, again","tags":[]}
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn exporting_to_ics_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut cmd = fx.cmd(["export", "--format", "ics"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    BEGIN:VCALENDAR
+    VERSION:2.0
+    PRODID:-//dhth//tomo//EN
+    CALSCALE:GREGORIAN
+    BEGIN:VEVENT
+    UID:20250106T090000Z-default-focus@tomo
+    DTSTAMP:20250106T092500Z
+    DTSTART:20250106T090000Z
+    DTEND:20250106T092500Z
+    SUMMARY:JIRA-123
+    DESCRIPTION:25 of 25 mins on timer "default"
+    CATEGORIES:backend,review
+    TRANSP:OPAQUE
+    X-TOMO-COMPLETED:TRUE
+    END:VEVENT
+    BEGIN:VEVENT
+    UID:20250106T092500Z-default-break@tomo
+    DTSTAMP:20250106T093000Z
+    DTSTART:20250106T092500Z
+    DTEND:20250106T093000Z
+    SUMMARY:Break
+    DESCRIPTION:5 mins on timer "default"
+    TRANSP:OPAQUE
+    X-TOMO-COMPLETED:TRUE
+    END:VEVENT
+    BEGIN:VEVENT
+    UID:20250107T140000Z-pairing-focus@tomo
+    DTSTAMP:20250107T141230Z
+    DTSTART:20250107T140000Z
+    DTEND:20250107T141230Z
+    SUMMARY:write docs\, again (interrupted)
+    DESCRIPTION:12 of 25 mins on timer "pairing" (interrupted)
+    TRANSP:OPAQUE
+    X-TOMO-COMPLETED:FALSE
+    END:VEVENT
+    END:VCALENDAR
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn exporting_a_time_range_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut cmd = fx.cmd([
+        "export",
+        "--since",
+        "2025-01-06T09:20:00Z",
+        "--until",
+        "2025-01-07T00:00:00Z",
+    ]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    start,end,kind,timer,planned_mins,actual_mins,completed,task,tags
+    2025-01-06T09:25:00+00:00,2025-01-06T09:30:00+00:00,break,default,,5,true,,
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn exporting_a_single_timer_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut cmd = fx.cmd(["export", "--timer", "pairing"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    start,end,kind,timer,planned_mins,actual_mins,completed,task,tags
+    2025-01-07T14:00:00+00:00,2025-01-07T14:12:30+00:00,focus,pairing,25,12,false,"write docs, again",
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn exporting_without_history_works() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    let mut cmd = fx.cmd(["export"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    start,end,kind,timer,planned_mins,actual_mins,completed,task,tags
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn a_cut_off_last_line_in_the_history_is_skipped_with_a_warning_once() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(&format!("{HISTORY}{{\"timer\":"));
+
+    // WHEN
+    let mut first = fx.cmd(["export", "--format", "jsonl"]);
+    let mut second = fx.cmd(["export", "--format", "jsonl"]);
+
+    // THEN
+    assert_cmd_snapshot!(first, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {"start":"2025-01-06T09:00:00+00:00","end":"2025-01-06T09:25:00+00:00","kind":"focus","timer":"default","planned_mins":25,"actual_mins":25,"completed":true,"task":"JIRA-123","tags":["backend","review"]}
+    {"start":"2025-01-06T09:25:00+00:00","end":"2025-01-06T09:30:00+00:00","kind":"break","timer":"default","planned_mins":null,"actual_mins":5,"completed":true,"task":null,"tags":[]}
+    {"start":"2025-01-07T14:00:00+00:00","end":"2025-01-07T14:12:30+00:00","kind":"focus","timer":"pairing","planned_mins":25,"actual_mins":12,"completed":false,"task":"write docs, again","tags":[]}
+
+    ----- stderr -----
+    warning: the last line of tomo's history file is cut off, skipping it: couldn't parse line 4: EOF while parsing a value at line 1 column 9
+    "#);
+    assert_cmd_snapshot!(second, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {"start":"2025-01-06T09:00:00+00:00","end":"2025-01-06T09:25:00+00:00","kind":"focus","timer":"default","planned_mins":25,"actual_mins":25,"completed":true,"task":"JIRA-123","tags":["backend","review"]}
+    {"start":"2025-01-06T09:25:00+00:00","end":"2025-01-06T09:30:00+00:00","kind":"break","timer":"default","planned_mins":null,"actual_mins":5,"completed":true,"task":null,"tags":[]}
+    {"start":"2025-01-07T14:00:00+00:00","end":"2025-01-07T14:12:30+00:00","kind":"focus","timer":"pairing","planned_mins":25,"actual_mins":12,"completed":false,"task":"write docs, again","tags":[]}
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn sessions_are_recorded_when_timer_state_changes() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut start_cmd = fx.cmd([
+        "start",
+        "--elapsed-mins",
+        "12",
+        "--task",
+        "JIRA-123",
+        "--tag",
+        "backend",
+    ]);
+    assert_cmd_snapshot!(start_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+    let mut break_cmd = fx.cmd(["break"]);
+    assert_cmd_snapshot!(break_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+    let mut stop_cmd = fx.cmd(["stop"]);
+    assert_cmd_snapshot!(stop_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+
+    // WHEN
+    let mut cmd = fx.cmd(["export", "--format", "csv"]);
+
+    // THEN
+    insta::with_settings!({filters => vec![
+        (r"\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?\+00:00", "[TIMESTAMP]"),
+    ]}, {
+        assert_cmd_snapshot!(cmd, @r"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        start,end,kind,timer,planned_mins,actual_mins,completed,task,tags
+        [TIMESTAMP],[TIMESTAMP],focus,default,25,12,false,JIRA-123,backend
+        [TIMESTAMP],[TIMESTAMP],break,default,,0,true,,
+
+        ----- stderr -----
+        ");
+    });
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn exporting_fails_for_invalid_time_bound() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    let mut cmd = fx.cmd(["export", "--since", "last week"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'last week' for '--since <DATE|TIME>': expected a date (YYYY-MM-DD) or an RFC 3339 timestamp
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn starting_fails_for_invalid_tag() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    let mut cmd = fx.cmd(["start", "--tag", "a,b"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: tags need to be non-empty, and cannot contain whitespace or ','
+    ");
+}
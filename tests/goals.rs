@@ -0,0 +1,288 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+/// Returns a history log entry for a pomodoro that started at 00:01 UTC, `days_ago` days ago, and
+/// ran for `mins` minutes.
+fn pomodoro(days_ago: i64, mins: i64) -> String {
+    let start = (Utc::now() - Duration::days(days_ago))
+        .date_naive()
+        .and_hms_opt(0, 1, 0)
+        .expect("time should be valid")
+        .and_utc();
+    let end = start + Duration::minutes(mins);
+
+    format!(
+        r#"{{"timer":"default","kind":"focus","start":"{}","end":"{}","planned_mins":25}}"#,
+        start.to_rfc3339(),
+        end.to_rfc3339()
+    )
+}
+
+fn history(pomodoros: &[(i64, i64)]) -> String {
+    pomodoros
+        .iter()
+        .map(|(days_ago, mins)| pomodoro(*days_ago, *mins) + "\n")
+        .collect()
+}
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn goal_placeholders_are_replaced_in_the_status_bar() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file("daily_goal = 8\n");
+    fx.write_history_file(&history(&[(0, 25), (0, 25), (0, 10), (1, 25)]));
+    fx.write_data_file("stop");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--idle-msg", "{today}/{goal}"]);
+    cmd.env("TZ", "UTC");
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     2/8 
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn goal_placeholders_work_in_pads() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file("daily_goal = 8\n");
+    fx.write_history_file(&history(&[(0, 25)]));
+    fx.write_data_file("break");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--right-pad", " [{today}/{goal}]"]);
+    cmd.env("TZ", "UTC");
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     \o/ [1/8]
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn goal_placeholders_show_a_dash_for_unset_goals() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_data_file("stop");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--idle-msg", "{today}/{goal}"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     0/- 
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn goal_placeholders_show_a_dash_when_the_history_cant_be_read() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file("daily_goal = 8\n");
+    fx.write_history_file(&format!("{{\"timer\":\n{}", history(&[(0, 25)])));
+    fx.write_data_file("stop");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--idle-msg", "{today}/{goal}"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     -/- 
+
+    ----- stderr -----
+    warning: couldn't count pomodoros for the goal placeholders: couldn't parse line 1 of tomo's history file: EOF while parsing a value at line 1 column 9
+    ");
+}
+
+#[test]
+fn status_bar_ignores_an_invalid_config_file_with_a_warning() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file("daily_gaol = 3\n");
+    fx.write_data_file("stop");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--idle-msg", "{today}/{goal}"]);
+    cmd.env("TZ", "UTC");
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     0/- 
+
+    ----- stderr -----
+    warning: ignoring tomo's config file: couldn't parse tomo's config file: TOML parse error at line 1, column 1
+      |
+    1 | daily_gaol = 3
+      | ^^^^^^^^^^
+    unknown field `daily_gaol`, expected one of `daily_goal`, `weekly_goal`, `idle_msg`, `accessible`
+    ");
+}
+
+#[test]
+fn stats_show_goal_hit_rate_and_streaks() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file("daily_goal = 2\n");
+    fx.write_history_file(&history(&[
+        (5, 25),
+        (5, 25),
+        (4, 25),
+        (2, 25),
+        (2, 25),
+        (1, 25),
+        (1, 25),
+        (1, 25),
+        (0, 25),
+        (0, 12),
+    ]));
+
+    // WHEN
+    let mut cmd = fx.cmd(["stats"]);
+    cmd.env("TZ", "UTC");
+
+    // THEN
+    insta::with_settings!({filters => vec![
+        (r"this week(\s+)\d+/", "this week$1[N]/"),
+    ]}, {
+        assert_cmd_snapshot!(cmd, @r"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        today           1/2
+        this week       [N]/-
+        daily goal met  3 of 5 days (60%)
+        daily streak    2 days (longest: 2 days)
+        weekly goal     not set
+
+        ----- stderr -----
+        ");
+    });
+}
+
+#[test]
+fn stats_work_without_any_history() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file("daily_goal = 8\nweekly_goal = 40\n");
+
+    // WHEN
+    let mut cmd = fx.cmd(["stats"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    today            0/8
+    this week        0/40
+    daily goal met   0 of 0 days (-)
+    daily streak     0 days (longest: 0 days)
+    weekly goal met  0 of 0 weeks (-)
+    weekly streak    0 weeks (longest: 0 weeks)
+
+    ----- stderr -----
+    ");
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn invalid_config_file_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file("daily_goal = \"lots\"\n");
+
+    // WHEN
+    let mut cmd = fx.cmd(["stats"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't parse tomo's config file
+
+    Caused by:
+        TOML parse error at line 1, column 14
+          |
+        1 | daily_goal = "lots"
+          |              ^^^^^^
+        invalid type: string "lots", expected u32
+    "#);
+}
+
+#[test]
+fn goal_placeholders_fail_outside_the_status_bar_when_the_history_cant_be_read() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(&format!("{{\"timer\":\n{}", history(&[(0, 25)])));
+
+    // WHEN
+    let mut cmd = fx.cmd(["--idle-msg", "{today}", "list"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't parse line 1 of tomo's history file
+
+    Caused by:
+        EOF while parsing a value at line 1 column 9
+    ");
+}
+
+#[test]
+fn zero_goals_fail() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file("daily_goal = 0\n");
+
+    // WHEN
+    let mut cmd = fx.cmd(["stats"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: goals in tomo's config file need to be greater than 0
+    ");
+}
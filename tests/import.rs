@@ -0,0 +1,403 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+const TOMO_JSON: &str = r#"[
+  {
+    "start": "2025-01-06T09:00:00+00:00",
+    "end": "2025-01-06T09:25:00+00:00",
+    "kind": "focus",
+    "timer": "default",
+    "planned_mins": 25,
+    "actual_mins": 25,
+    "completed": true,
+    "task": "JIRA-123",
+    "tags": ["backend", "review"]
+  },
+  {
+    "start": "2025-01-06T09:25:00+00:00",
+    "end": "2025-01-06T09:30:00+00:00",
+    "kind": "break",
+    "timer": "default",
+    "planned_mins": null,
+    "actual_mins": 5,
+    "completed": true,
+    "task": null,
+    "tags": []
+  }
+]
+"#;
+
+const OTHER_APP_CSV: &str = r#"Started At,Minutes,Type,Project,Labels
+2025-01-06 09:00,25,Pomodoro,JIRA-123,backend;review
+2025-01-06 09:25,5,Break,,
+2025-01-06 09:30,17.5,pomodoro,"write docs, again",
+"#;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn importing_tomos_json_export_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let file = fx.write_file("export.json", TOMO_JSON);
+
+    // WHEN
+    let mut cmd = fx.cmd(["import", "--from", "json", &file]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    imported 2 session(s), skipped 0 duplicate(s)
+
+    ----- stderr -----
+    ");
+    assert_eq!(
+        fx.read_history_file(),
+        r#"{"timer":"default","kind":"focus","start":"2025-01-06T09:00:00Z","end":"2025-01-06T09:25:00Z","planned_mins":25,"task":"JIRA-123","tags":["backend","review"]}
+{"timer":"default","kind":"break","start":"2025-01-06T09:25:00Z","end":"2025-01-06T09:30:00Z"}
+"#
+    );
+}
+
+#[test]
+fn importing_tomos_csv_export_works_without_column_mappings() {
+    // GIVEN
+    let fx = Fixture::new();
+    let file = fx.write_file(
+        "export.csv",
+        r#"start,end,kind,timer,planned_mins,actual_mins,completed,task,tags
+2025-01-06T09:00:00+00:00,2025-01-06T09:25:00+00:00,focus,default,25,25,true,JIRA-123,"backend,review"
+2025-01-07T14:00:00+00:00,2025-01-07T14:12:30+00:00,focus,pairing,25,12,false,"write docs, again",
+"#,
+    );
+
+    // WHEN
+    let mut import_cmd = fx.cmd(["import", "--from", "csv", &file]);
+    let mut export_cmd = fx.cmd(["export", "--format", "csv"]);
+
+    // THEN
+    assert_cmd_snapshot!(import_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    imported 2 session(s), skipped 0 duplicate(s)
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(export_cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    start,end,kind,timer,planned_mins,actual_mins,completed,task,tags
+    2025-01-06T09:00:00+00:00,2025-01-06T09:25:00+00:00,focus,default,25,25,true,JIRA-123,"backend,review"
+    2025-01-07T14:00:00+00:00,2025-01-07T14:12:30+00:00,focus,pairing,25,12,false,"write docs, again",
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn importing_a_generic_csv_with_column_mappings_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let file = fx.write_file("other-app.csv", OTHER_APP_CSV);
+
+    // WHEN
+    let mut import_cmd = fx.cmd([
+        "import",
+        "--from",
+        "csv",
+        &file,
+        "--column",
+        "start=Started At",
+        "--column",
+        "duration_mins=Minutes",
+        "--column",
+        "kind=Type",
+        "--column",
+        "task=Project",
+        "--column",
+        "tags=Labels",
+        "--tags-sep",
+        ";",
+        "--timer",
+        "imported",
+    ]);
+    import_cmd.env("TZ", "UTC");
+    let mut export_cmd = fx.cmd(["export", "--format", "csv"]);
+
+    // THEN
+    assert_cmd_snapshot!(import_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    imported 3 session(s), skipped 0 duplicate(s)
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(export_cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    start,end,kind,timer,planned_mins,actual_mins,completed,task,tags
+    2025-01-06T09:00:00+00:00,2025-01-06T09:25:00+00:00,focus,imported,25,25,true,JIRA-123,"backend,review"
+    2025-01-06T09:25:00+00:00,2025-01-06T09:30:00+00:00,break,imported,,5,true,,
+    2025-01-06T09:30:00+00:00,2025-01-06T09:47:30+00:00,focus,imported,25,17,false,"write docs, again",
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn imported_focus_sessions_without_a_planned_length_are_pomodoros() {
+    // GIVEN
+    let fx = Fixture::new();
+    let file = fx.write_file(
+        "short.csv",
+        "start,duration_mins,kind
+2025-01-06T09:00:00Z,3,focus
+2025-01-06T09:03:00Z,5,break
+",
+    );
+
+    // WHEN
+    let mut import_cmd = fx.cmd(["import", "--from", "csv", &file]);
+    let mut export_cmd = fx.cmd(["export", "--format", "csv"]);
+
+    // THEN
+    assert_cmd_snapshot!(import_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    imported 2 session(s), skipped 0 duplicate(s)
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(export_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    start,end,kind,timer,planned_mins,actual_mins,completed,task,tags
+    2025-01-06T09:00:00+00:00,2025-01-06T09:03:00+00:00,focus,default,25,3,false,,
+    2025-01-06T09:03:00+00:00,2025-01-06T09:08:00+00:00,break,default,,5,true,,
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn importing_skips_sessions_with_a_start_time_that_is_already_recorded() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(
+        r#"{"timer":"default","kind":"focus","start":"2025-01-06T09:00:00Z","end":"2025-01-06T09:20:00Z","planned_mins":25}
+"#,
+    );
+    let file = fx.write_file("export.json", TOMO_JSON);
+
+    // WHEN
+    let mut first_cmd = fx.cmd(["import", "--from", "json", &file]);
+    let mut second_cmd = fx.cmd(["import", "--from", "json", &file]);
+
+    // THEN
+    assert_cmd_snapshot!(first_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    imported 1 session(s), skipped 1 duplicate(s)
+
+    ----- stderr -----
+    ");
+    assert_cmd_snapshot!(second_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    imported 0 session(s), skipped 2 duplicate(s)
+
+    ----- stderr -----
+    ");
+    assert_eq!(fx.read_history_file().lines().count(), 2);
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn importing_a_csv_without_a_start_column_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    let file = fx.write_file("other-app.csv", OTHER_APP_CSV);
+
+    // WHEN
+    let mut cmd = fx.cmd(["import", "--from", "csv", &file]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: CSV has no start column; map one with --column start=COLUMN
+    ");
+}
+
+#[test]
+fn importing_a_csv_with_a_mapping_to_a_missing_column_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    let file = fx.write_file("other-app.csv", OTHER_APP_CSV);
+
+    // WHEN
+    let mut cmd = fx.cmd([
+        "import",
+        "--from",
+        "csv",
+        &file,
+        "--column",
+        "start=Start Time",
+    ]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: column "Start Time" (mapped to start) is not in the CSV header
+    "#);
+}
+
+#[test]
+fn importing_a_csv_with_an_invalid_row_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    let file = fx.write_file(
+        "other-app.csv",
+        "start,end\n2025-01-06T09:00:00Z,2025-01-06T09:25:00Z\nyesterday,2025-01-06T10:25:00Z\n",
+    );
+
+    // WHEN
+    let mut cmd = fx.cmd(["import", "--from", "csv", &file]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: line 3 of the CSV is invalid
+
+    Caused by:
+        couldn't parse time "yesterday"; expected an RFC 3339 timestamp or YYYY-MM-DD HH:MM[:SS]
+    "#);
+}
+
+#[test]
+fn importing_a_csv_with_an_out_of_range_duration_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    let file = fx.write_file(
+        "other-app.csv",
+        "start,duration_mins\n2025-01-06T09:00:00Z,25\n2025-01-06T10:00:00Z,1e15\n",
+    );
+
+    // WHEN
+    let mut cmd = fx.cmd(["import", "--from", "csv", &file]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: line 3 of the CSV is invalid
+
+    Caused by:
+        duration_mins is out of range
+    ");
+}
+
+#[test]
+fn importing_a_csv_with_a_non_finite_duration_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    let file = fx.write_file(
+        "other-app.csv",
+        "start,duration_mins\n2025-01-06T09:00:00Z,NaN\n",
+    );
+
+    // WHEN
+    let mut cmd = fx.cmd(["import", "--from", "csv", &file]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: line 2 of the CSV is invalid
+
+    Caused by:
+        duration_mins needs to be a non-negative number
+    ");
+}
+
+#[test]
+fn importing_a_session_that_ends_before_it_starts_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    let file = fx.write_file(
+        "export.jsonl",
+        r#"{"start":"2025-01-06T09:25:00Z","end":"2025-01-06T09:00:00Z","kind":"focus"}"#,
+    );
+
+    // WHEN
+    let mut cmd = fx.cmd(["import", "--from", "jsonl", &file]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: line 1 is invalid
+
+    Caused by:
+        session ends before it starts
+    ");
+}
+
+#[test]
+fn importing_with_an_invalid_column_mapping_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+    let file = fx.write_file("other-app.csv", OTHER_APP_CSV);
+
+    // WHEN
+    let mut cmd = fx.cmd(["import", "--from", "csv", &file, "--column", "begin=Start"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'begin=Start' for '--column <FIELD=COLUMN>': unknown field; expected one of: start, end, duration_mins, kind, timer, planned_mins, task, tags
+
+    For more information, try '--help'.
+    ");
+}
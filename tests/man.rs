@@ -0,0 +1,230 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn generating_man_page_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["man"]);
+
+    // WHEN
+    // THEN
+    // the sqlite feature adds a subcommand, which is left out so that one snapshot covers both builds
+    insta::with_settings!({filters => vec![
+        (r"\n\.TP\ntomo\\-migrate\(1\)\n[^\n]*", ""),
+    ]}, {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        .ie \n(.g .ds Aq \(aq
+        .el .ds Aq '
+        .TH TOMO 1  tomo 
+        .SH NAME
+        tomo \- tomo is a no\-frills pomodoro progress indicator for tmux
+        .SH SYNOPSIS
+        \fBtomo\fR [\fB\-p\fR|\fB\-\-pending\-block\fR] [\fB\-c\fR|\fB\-\-complete\-block\fR] [\fB\-l\fR|\fB\-\-left\-pad\fR] [\fB\-r\fR|\fB\-\-right\-pad\fR] [\fB\-d\fR|\fB\-\-delimiter\fR] [\fB\-n\fR|\fB\-\-num\-blocks\fR] [\fB\-\-finished\-msg\fR] [\fB\-\-break\-msg\fR] [\fB\-\-idle\-msg\fR] [\fB\-\-data\-file\fR] [\fB\-\-config\-file\fR] [\fB\-t\fR|\fB\-\-timer\fR] [\fB\-\-timer\-sep\fR] [\fB\-o\fR|\fB\-\-output\fR] [\fB\-\-accessible\fR] [\fB\-\-tmux\-scope\fR] [\fB\-\-strict\fR] [\fB\-h\fR|\fB\-\-help\fR] [\fIsubcommands\fR]
+        .SH DESCRIPTION
+        tomo is a no\-frills pomodoro progress indicator for tmux
+        .SH OPTIONS
+        .TP
+        \fB\-p\fR, \fB\-\-pending\-block\fR \fI<STRING>\fR [default: ▫]
+        String to represent a "pending" block in the progress bar
+        .TP
+        \fB\-c\fR, \fB\-\-complete\-block\fR \fI<STRING>\fR [default: ▪]
+        String to represent a "complete" block in the progress bar
+        .TP
+        \fB\-l\fR, \fB\-\-left\-pad\fR \fI<STRING>\fR [default:  ]
+        String to pad the output with on the LHS
+        .TP
+        \fB\-r\fR, \fB\-\-right\-pad\fR \fI<STRING>\fR [default:  ]
+        String to pad the output with on the RHS
+        .TP
+        \fB\-d\fR, \fB\-\-delimiter\fR \fI<STRING>\fR [default: ]
+        Delimiter between progress bar chunks
+        .TP
+        \fB\-n\fR, \fB\-\-num\-blocks\fR \fI<NUM>\fR [default: 10]
+        Number of blocks to show in progress bar
+        .TP
+        \fB\-\-finished\-msg\fR \fI<STRING>\fR [default: done]
+        Message to show when timer is finished
+        .TP
+        \fB\-\-break\-msg\fR \fI<STRING>\fR [default: \\o/]
+        Message to show when on a break
+        .TP
+        \fB\-\-idle\-msg\fR \fI<STRING>\fR
+        Message to show when no timer is running (defaults to the config file\*(Aqs idle_msg); "{idle}" is replaced with how long it\*(Aqs been stopped
+        .TP
+        \fB\-\-data\-file\fR \fI<STRING>\fR
+        tomo\*(Aqs data file (defaults to <YOUR_DATA_DIR>/tomo/.tomo)
+        .TP
+        \fB\-\-config\-file\fR \fI<STRING>\fR
+        tomo\*(Aqs config file (defaults to <YOUR_CONFIG_DIR>/tomo/config.toml)
+        .TP
+        \fB\-t\fR, \fB\-\-timer\fR \fI<NAME>\fR
+        Named timer to act on; can be repeated to show several timers in one segment
+        .TP
+        \fB\-\-timer\-sep\fR \fI<STRING>\fR [default:  | ]
+        Separator between timers when showing several of them
+        .TP
+        \fB\-o\fR, \fB\-\-output\fR \fI<FORMAT>\fR [default: plain]
+        How to show the timer\*(Aqs progress
+        .br
+
+        .br
+        \fIPossible values:\fR
+        .RS 14
+        .IP \(bu 2
+        plain
+        .IP \(bu 2
+        tmux
+        .IP \(bu 2
+        ansi
+        .IP \(bu 2
+        json
+        .IP \(bu 2
+        waybar
+        .RE
+        .TP
+        \fB\-\-accessible\fR
+        Describe progress in words (eg. "focus, 14 minutes left") rather than with a progress bar
+        .TP
+        \fB\-\-tmux\-scope\fR
+        Scope the timer to the current tmux session (falls back to the global timer outside tmux)
+        .TP
+        \fB\-\-strict\fR
+        Fail if tomo\*(Aqs data file is missing or corrupt, instead of treating the timer as stopped
+        .TP
+        \fB\-h\fR, \fB\-\-help\fR
+        Print help
+        .SH SUBCOMMANDS
+        .TP
+        tomo\-start(1)
+        Start a pomodoro timer
+        .TP
+        tomo\-stop(1)
+        Stop timer
+        .TP
+        tomo\-break(1)
+        Start a break
+        .TP
+        tomo\-list(1)
+        List all active timers
+        .TP
+        tomo\-status(1)
+        Show the timer\*(Aqs state, times, task, and today\*(Aqs count, one per line
+        .TP
+        tomo\-completions(1)
+        Print the script that sets up shell completions for tomo
+        .TP
+        tomo\-man(1)
+        Print tomo\*(Aqs man page
+        .TP
+        tomo\-export(1)
+        Export recorded pomodoros and breaks
+        .TP
+        tomo\-import(1)
+        Import pomodoros and breaks from another app, or from tomo\*(Aqs own export
+        .TP
+        tomo\-stats(1)
+        Show progress towards your goals, and how often you\*(Aqve met them
+        .TP
+        tomo\-report(1)
+        Report on recorded pomodoros
+        .TP
+        tomo\-tui(1)
+        Show a full\-screen view of the timer, with key bindings to start, break, and stop it
+        .TP
+        tomo\-clock(1)
+        Show the time left in big digits, with the progress bar underneath
+        .TP
+        tomo\-doctor(1)
+        Check tomo\*(Aqs setup for problems (eg. timestamps in the future, after a clock jump)
+        .TP
+        tomo\-help(1)
+        Print this message or the help of the given subcommand(s)
+        .ie \n(.g .ds Aq \(aq
+        .el .ds Aq '
+        .SH "DATA FILE"
+        tomo keeps the state of a timer in a plain text data file, which holds one of the following:
+        .TP
+        \fITIMESTAMP\fR
+        A pomodoro is running, and was started at TIMESTAMP (in RFC 3339 format).
+        .TP
+        \fBbreak\fR [\fITIMESTAMP\fR]
+        A break is running, and was started at TIMESTAMP (if known).
+        .TP
+        \fBstop\fR [\fITIMESTAMP\fR]
+        The timer is stopped, and was stopped at TIMESTAMP (if known). A missing data file is treated the same way, unless \-\-strict is passed.
+        .PP
+        While a pomodoro is running, further lines can hold the task it\*(Aqs for ("task: LABEL") and its tags ("tags: TAG1,TAG2").
+        .PP
+        The data file is replaced atomically on every write. Concurrent invocations of tomo are serialised via an advisory lock on a ".lock" file next to the data file.
+        .SH "CONFIG FILE"
+        tomo reads optional settings from a TOML config file. A missing config file is the same as an empty one. The following settings are supported:
+        .TP
+        \fBdaily_goal\fR = \fINUM\fR
+        Number of pomodoros to aim for each day.
+        .TP
+        \fBweekly_goal\fR = \fINUM\fR
+        Number of pomodoros to aim for each week (weeks start on Monday).
+        .TP
+        \fBidle_msg\fR = \fISTRING\fR
+        Message to show when no timer is running, unless \-\-idle\-msg is passed.
+        .TP
+        \fBaccessible\fR = \fIBOOL\fR
+        Describe progress in words (eg. "focus, 14 minutes left") rather than with a progress bar, in every output format; the same as passing \-\-accessible.
+        .PP
+        In the messages and pads shown in the status bar, "{today}" and "{week}" are replaced with the number of pomodoros completed today and this week, and "{goal}" and "{weekly_goal}" with the goals for them (or "\-" if not set). "tomo stats" shows how often the goals were met.
+        .SH FILES
+        .TP
+        \fI<YOUR_DATA_DIR>/tomo/.tomo\fR
+        Data file for the default timer.
+        .TP
+        \fI<YOUR_DATA_DIR>/tomo/.tomo\-NAME\fR
+        Data file for the timer NAME. Named timers are always kept next to the default timer\*(Aqs data file, including when \-\-data\-file is passed.
+        .TP
+        \fI<YOUR_DATA_DIR>/tomo/.tomo.history\fR
+        History log, shared by all timers. Every finished pomodoro and break is appended to it as a JSON object on its own line.
+        .TP
+        \fI<YOUR_DATA_DIR>/tomo/.tomo.db\fR
+        SQLite database that takes over from the history log once "tomo migrate" has created it (only in builds with the sqlite feature).
+        .TP
+        \fI<YOUR_DATA_DIR>/tomo/.tomo.lock\fR
+        Lock file that keeps tomo processes from changing a timer\*(Aqs data file at the same time. Each data file has one next to it (eg. .tomo\-NAME.lock), and appends to the history log are guarded by .tomo.history.lock.
+        .TP
+        \fI<YOUR_CONFIG_DIR>/tomo/config.toml\fR
+        Config file.
+        .SH ENVIRONMENT
+        .TP
+        \fBTOMO_DATA_FILE\fR
+        Data file to use, same as \-\-data\-file.
+        .TP
+        \fBTOMO_CONFIG_FILE\fR
+        Config file to use, same as \-\-config\-file.
+        .TP
+        \fBTOMO_TMUX_SCOPE\fR
+        If set to true, keep a separate timer for each tmux session, same as \-\-tmux\-scope.
+        .TP
+        \fBTOMO_ACCESSIBLE\fR
+        If set to true, describe progress in words rather than with a progress bar, same as \-\-accessible.
+        .TP
+        \fBTMUX\fR
+        Used to detect whether tomo is running inside tmux when scoping timers to tmux sessions.
+        .TP
+        \fBNO_COLOR\fR
+        If set, "tomo report" doesn\*(Aqt colour its output unless \-\-color always is passed.
+        .TP
+        \fBCOMPLETE\fR
+        Set by the scripts printed by "tomo completions" when asking tomo for completions.
+
+        ----- stderr -----
+        "#);
+    });
+}
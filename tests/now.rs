@@ -0,0 +1,131 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn progress_can_be_shown_at_a_given_time() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.cmd(["start", "--now", "2025-01-06T09:00:00Z"])
+        .output()
+        .expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--now", "2025-01-06T09:10:00Z"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     ▪▪▪▪▫▫▫▫▫▫ 
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn time_can_be_given_via_the_environment() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_data_file("2025-01-06T09:00:00Z");
+
+    // WHEN
+    let mut cmd = fx.base_cmd();
+    cmd.env("TOMO_NOW", "2025-01-06T09:25:00Z");
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     done 
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn ending_a_break_records_it() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.cmd(["start", "--now", "2025-01-06T09:00:00Z"])
+        .output()
+        .expect("command should've run");
+    fx.cmd(["break", "--now", "2025-01-06T09:30:00Z"])
+        .output()
+        .expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd(["stop", "--now", "2025-01-06T09:35:00Z"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+    insta::assert_snapshot!(fx.read_history_file(), @r#"
+    {"timer":"default","kind":"focus","start":"2025-01-06T09:00:00Z","end":"2025-01-06T09:25:00Z","planned_mins":25}
+    {"timer":"default","kind":"break","start":"2025-01-06T09:30:00Z","end":"2025-01-06T09:35:00Z"}
+    "#);
+}
+
+#[test]
+fn todays_count_resets_at_midnight() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(
+        r#"{"timer":"default","kind":"focus","start":"2025-01-06T09:00:00Z","end":"2025-01-06T09:25:00Z","planned_mins":25}
+"#,
+    );
+    fx.write_data_file("stop");
+
+    // WHEN
+    let before_midnight = fx
+        .cmd(["--idle-msg", "{today}", "--now", "2025-01-06T23:59:59Z"])
+        .env("TZ", "UTC")
+        .output()
+        .expect("command should've run");
+    let after_midnight = fx
+        .cmd(["--idle-msg", "{today}", "--now", "2025-01-07T00:00:00Z"])
+        .env("TZ", "UTC")
+        .output()
+        .expect("command should've run");
+
+    // THEN
+    assert_eq!(String::from_utf8_lossy(&before_midnight.stdout), " 1 \n");
+    assert_eq!(String::from_utf8_lossy(&after_midnight.stdout), " 0 \n");
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn invalid_time_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    let mut cmd = fx.cmd(["--now", "yesterday"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'yesterday' for '--now <TIME>': input contains invalid characters
+
+    For more information, try '--help'.
+    ");
+}
@@ -0,0 +1,248 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn progress_can_be_styled_for_tmux() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut start_cmd = fx.cmd(["start", "--elapsed-mins", "10"]);
+    start_cmd.output().expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--output", "tmux"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     #[fg=green]▪▪▪▪#[default]#[fg=brightblack]▫▫▫▫▫▫#[default] 
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn progress_can_be_shown_as_json() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut start_cmd = fx.cmd(["start", "--elapsed-mins", "10", "--task", "JIRA-123"]);
+    start_cmd.output().expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--output", "json"]);
+
+    // THEN
+    insta::with_settings!({filters => vec![
+        (r#""(elapsed|remaining)_seconds":\d+"#, r#""${1}_seconds":[N]"#),
+    ]}, {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        {"timer":"default","state":"running","elapsed_seconds":[N],"remaining_seconds":[N],"task":"JIRA-123","text":" ▪▪▪▪▫▫▫▫▫▫ "}
+
+        ----- stderr -----
+        "#);
+    });
+}
+
+#[test]
+fn json_output_warns_about_start_times_in_the_future() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.cmd(["start", "--now", "2025-01-06T11:05:00Z"])
+        .output()
+        .expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--output", "json", "--now", "2025-01-06T09:00:00Z"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {"timer":"default","state":"running","elapsed_seconds":0,"remaining_seconds":1500,"text":" ▫▫▫▫▫▫▫▫▫▫ ","warning":"clock_skew","skew_seconds":7500}
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn json_output_includes_stopped_timers() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut start_cmd = fx.cmd(["start", "--timer", "pairing"]);
+    start_cmd.output().expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd([
+        "--timer", "default", "--timer", "pairing", "--output", "json",
+    ]);
+
+    // THEN
+    insta::with_settings!({filters => vec![
+        (r#""(elapsed|remaining)_seconds":\d+"#, r#""${1}_seconds":[N]"#),
+    ]}, {
+        assert_cmd_snapshot!(cmd, @r#"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        [{"timer":"default","state":"stopped","text":""},{"timer":"pairing","state":"running","elapsed_seconds":[N],"remaining_seconds":[N],"text":" ▫▫▫▫▫▫▫▫▫▫ "}]
+
+        ----- stderr -----
+        "#);
+    });
+}
+
+#[test]
+fn progress_can_be_shown_in_waybar() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_data_file("break");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--output", "waybar"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {"text":" \\o/ ","tooltip":"default: on a break","class":"break","percentage":0}
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn progress_can_be_described_in_words() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.cmd(["start", "--now", "2025-01-06T09:00:00Z"])
+        .output()
+        .expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--accessible", "--now", "2025-01-06T09:10:30Z"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     focus, 15 minutes left 
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn accessible_mode_can_be_turned_on_via_the_environment() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.cmd(["break"]).output().expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--output", "tmux"]);
+    cmd.env("TOMO_ACCESSIBLE", "true");
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     #[fg=cyan]break#[default] 
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn accessible_mode_can_be_turned_on_in_the_config_file() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file("accessible = true\n");
+    fx.cmd([
+        "start",
+        "--timer",
+        "pairing",
+        "--now",
+        "2025-01-06T09:00:00Z",
+    ])
+    .output()
+    .expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd(["--output", "waybar", "--timer", "pairing"]);
+    cmd.args(["--now", "2025-01-06T09:24:30Z"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    {"text":" focus, 1 minute left ","tooltip":"pairing: focus, 1 minute left","class":"running","percentage":98}
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn active_timers_are_described_in_words_in_the_accessible_mode() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.cmd(["start", "--now", "2025-01-06T09:00:00Z"])
+        .output()
+        .expect("command should've run");
+    fx.cmd(["break", "--timer", "pairing"])
+        .output()
+        .expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd(["list", "--now", "2025-01-06T09:20:00Z"]);
+    cmd.env("TOMO_ACCESSIBLE", "true");
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    default  focus, 5 minutes left
+    pairing  break
+
+    ----- stderr -----
+    ");
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn unknown_output_format_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    let mut cmd = fx.cmd(["--output", "html"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'html' for '--output <FORMAT>'
+      [possible values: plain, tmux, ansi, json, waybar]
+
+    For more information, try '--help'.
+    ");
+}
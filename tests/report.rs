@@ -0,0 +1,347 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+const HISTORY: &str = r#"{"timer":"default","kind":"focus","start":"2025-01-06T09:00:00Z","end":"2025-01-06T09:25:00Z","planned_mins":25,"task":"JIRA-123","tags":["backend","review"]}
+{"timer":"default","kind":"break","start":"2025-01-06T09:25:00Z","end":"2025-01-06T09:30:00Z"}
+{"timer":"default","kind":"focus","start":"2025-01-06T09:30:00Z","end":"2025-01-06T09:55:00Z","planned_mins":25,"task":"JIRA-123","tags":["backend"]}
+{"timer":"pairing","kind":"focus","start":"2025-01-07T14:00:00Z","end":"2025-01-07T14:12:30Z","planned_mins":25,"task":"write docs | again"}
+{"timer":"default","kind":"focus","start":"2025-01-08T10:00:00Z","end":"2025-01-08T10:25:00Z","planned_mins":25,"tags":["review"]}
+"#;
+
+/// Returns a history log entry for a completed pomodoro that started at `hour`:10 UTC, today.
+fn pomodoro_today(hour: u32) -> String {
+    let start = Utc::now()
+        .date_naive()
+        .and_hms_opt(hour, 10, 0)
+        .expect("time should be valid")
+        .and_utc();
+    let end = start + Duration::minutes(25);
+
+    format!(
+        r#"{{"timer":"default","kind":"focus","start":"{}","end":"{}","planned_mins":25}}"#,
+        start.to_rfc3339(),
+        end.to_rfc3339()
+    )
+}
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn heatmap_report_shows_pomodoros_by_day_and_hour() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(&format!(
+        "{}\n{}\n{}\n",
+        pomodoro_today(9),
+        pomodoro_today(9),
+        pomodoro_today(11)
+    ));
+
+    // WHEN
+    let output = fx
+        .cmd(["report", "--heatmap", "--weeks", "4", "--color", "never"])
+        .env("TZ", "UTC")
+        .output()
+        .expect("command should've run");
+
+    // THEN
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("output should be valid utf-8");
+    let (heatmap, histogram) = stdout
+        .split_once("\n\n3 pomodoros in the last 4 weeks\n\n")
+        .expect("output should've had a summary");
+    assert_eq!(heatmap.lines().count(), 9);
+    assert!(heatmap.contains('█'));
+    assert!(!heatmap.contains('\x1b'));
+    insta::assert_snapshot!(histogram, @r"
+    pomodoros by hour of day
+    09:00  ████████████████████████████████████████ 2
+    10:00
+    11:00  ████████████████████ 1
+    ");
+}
+
+#[test]
+fn heatmap_report_can_be_coloured() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(&format!("{}\n", pomodoro_today(9)));
+
+    // WHEN
+    let output = fx
+        .cmd(["report", "--heatmap", "--color", "always"])
+        .output()
+        .expect("command should've run");
+
+    // THEN
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("output should be valid utf-8");
+    assert!(stdout.contains("\x1b[38;5;40m█\x1b[0m"));
+}
+
+#[test]
+fn heatmap_report_works_without_any_history() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    let output = fx
+        .cmd(["report", "--heatmap", "--weeks", "1", "--color", "never"])
+        .output()
+        .expect("command should've run");
+
+    // THEN
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("output should be valid utf-8");
+    assert!(stdout.ends_with("0 pomodoros in the last 1 week\n\nno pomodoros in this period\n"));
+}
+
+#[test]
+fn report_by_task_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut cmd = fx.cmd(["report", "--by", "task", "--period", "all"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    task                pomodoros  minutes
+    JIRA-123                    2       50
+    (no task)                   1       25
+    write docs | again          0       12
+    total                       3       87
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn report_by_tag_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut cmd = fx.cmd(["report", "--by", "tag", "--period", "all"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    tag       pomodoros  minutes
+    backend           2       50
+    review            2       50
+    (no tag)          0       12
+    total             3       87
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn report_can_be_sorted() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut cmd = fx.cmd([
+        "report", "--by", "task", "--period", "all", "--sort", "label",
+    ]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    task                pomodoros  minutes
+    (no task)                   1       25
+    JIRA-123                    2       50
+    write docs | again          0       12
+    total                       3       87
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn report_can_be_printed_as_csv_without_a_total_row() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut cmd = fx.cmd([
+        "report", "--by", "tag", "--period", "all", "--format", "csv",
+    ]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    tag,pomodoros,minutes
+    backend,2,50
+    review,2,50
+    (no tag),0,12
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn report_can_be_printed_as_markdown() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut cmd = fx.cmd([
+        "report", "--by", "task", "--period", "all", "--format", "markdown",
+    ]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    | task | pomodoros | minutes |
+    | --- | ---: | ---: |
+    | JIRA-123 | 2 | 50 |
+    | (no task) | 1 | 25 |
+    | write docs \| again | 0 | 12 |
+    | **total** | **3** | **87** |
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn report_can_be_limited_to_a_timer() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut cmd = fx.cmd([
+        "report", "--by", "task", "--period", "all", "--timer", "pairing",
+    ]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    task                pomodoros  minutes
+    write docs | again          0       12
+    total                       0       12
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn report_only_includes_the_current_period() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(&format!("{HISTORY}{}\n", pomodoro_today(0)));
+
+    // WHEN
+    let mut cmd = fx.cmd(["report", "--by", "task", "--period", "day"]);
+    cmd.env("TZ", "UTC");
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    task       pomodoros  minutes
+    (no task)          1       25
+    total              1       25
+
+    ----- stderr -----
+    ");
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn report_with_both_heatmap_and_by_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    let mut cmd = fx.cmd(["report", "--heatmap", "--by", "task"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the argument '--heatmap' cannot be used with '--by <LABEL>'
+
+    Usage: tomo report --data-file <STRING> --config-file <STRING> <--heatmap|--by <LABEL>>
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn report_without_a_kind_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    let mut cmd = fx.cmd(["report"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the following required arguments were not provided:
+      <--heatmap|--by <LABEL>>
+
+    Usage: tomo report --data-file <STRING> --config-file <STRING> <--heatmap|--by <LABEL>>
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn heatmap_report_with_zero_weeks_fails() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    let mut cmd = fx.cmd(["report", "--heatmap", "--weeks", "0"]);
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value '0' for '--weeks <NUM>': 0 is not in 1..=104
+
+    For more information, try '--help'.
+    ");
+}
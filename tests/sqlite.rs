@@ -0,0 +1,130 @@
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+const HISTORY: &str = r#"{"timer":"default","kind":"focus","start":"2025-01-06T09:00:00Z","end":"2025-01-06T09:25:00Z","planned_mins":25,"task":"JIRA-123","tags":["backend","review"]}
+{"timer":"pairing","kind":"focus","start":"2025-01-07T14:00:00Z","end":"2025-01-07T14:12:30Z","planned_mins":25}
+"#;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn migrating_the_history_log_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+
+    // WHEN
+    let mut migrate_cmd = fx.cmd(["migrate"]);
+
+    // THEN
+    insta::with_settings!({filters => vec![
+        (r"to \S+\.tomo\.db", "to [DATABASE]"),
+    ]}, {
+        assert_cmd_snapshot!(migrate_cmd, @r"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        migrated 2 session(s) to [DATABASE], skipped 0 duplicate(s)
+
+        ----- stderr -----
+        ");
+    });
+
+    // the history log is no longer read once there's a database
+    fx.write_history_file("");
+    let mut export_cmd = fx.cmd(["export", "--format", "csv", "--timer", "default"]);
+    assert_cmd_snapshot!(export_cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    start,end,kind,timer,planned_mins,actual_mins,completed,task,tags
+    2025-01-06T09:00:00+00:00,2025-01-06T09:25:00+00:00,focus,default,25,25,true,JIRA-123,"backend,review"
+
+    ----- stderr -----
+    "#);
+}
+
+#[test]
+fn migrating_twice_skips_sessions_already_in_the_database() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(HISTORY);
+    fx.cmd(["migrate"]).output().expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd(["migrate"]);
+
+    // THEN
+    insta::with_settings!({filters => vec![
+        (r"to \S+\.tomo\.db", "to [DATABASE]"),
+    ]}, {
+        assert_cmd_snapshot!(cmd, @r"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        migrated 0 session(s) to [DATABASE], skipped 2 duplicate(s)
+
+        ----- stderr -----
+        ");
+    });
+}
+
+#[test]
+fn migrating_skips_sessions_recorded_twice_in_the_history_log() {
+    // GIVEN
+    let fx = Fixture::new();
+    let first_line = HISTORY.lines().next().unwrap_or_default();
+    fx.write_history_file(&format!("{HISTORY}{first_line}\n"));
+
+    // WHEN
+    let mut cmd = fx.cmd(["migrate"]);
+
+    // THEN
+    insta::with_settings!({filters => vec![
+        (r"to \S+\.tomo\.db", "to [DATABASE]"),
+    ]}, {
+        assert_cmd_snapshot!(cmd, @r"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        migrated 2 session(s) to [DATABASE], skipped 1 duplicate(s)
+
+        ----- stderr -----
+        ");
+    });
+}
+
+#[test]
+fn finished_sessions_are_recorded_in_the_database() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.cmd(["migrate"]).output().expect("command should've run");
+    fx.cmd(["start", "--task", "JIRA-123"])
+        .output()
+        .expect("command should've run");
+
+    // WHEN
+    let mut stop_cmd = fx.cmd(["stop"]);
+
+    // THEN
+    assert_cmd_snapshot!(stop_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+    let output = fx
+        .cmd(["export", "--format", "jsonl"])
+        .output()
+        .expect("command should've run");
+    let stdout = String::from_utf8(output.stdout).expect("output should be valid utf-8");
+    assert_eq!(stdout.lines().count(), 1);
+    assert!(stdout.contains(r#""task":"JIRA-123""#));
+}
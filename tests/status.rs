@@ -0,0 +1,97 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn status_shows_a_running_pomodoro() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_history_file(
+        r#"{"timer":"default","kind":"focus","start":"2025-01-06T08:00:00Z","end":"2025-01-06T08:25:00Z","planned_mins":25}
+{"timer":"default","kind":"break","start":"2025-01-06T08:25:00Z","end":"2025-01-06T08:30:00Z"}
+"#,
+    );
+    fx.write_config_file("daily_goal = 8\n");
+    fx.cmd(["start", "--task", "JIRA-123"])
+        .args(["--tag", "backend", "--tag", "review"])
+        .args(["--now", "2025-01-06T09:00:00Z"])
+        .output()
+        .expect("command should've run");
+
+    // WHEN
+    let mut cmd = fx.cmd(["status", "--now", "2025-01-06T09:10:30Z"]);
+    cmd.env("TZ", "UTC");
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    timer        default
+    state        running
+    started at   2025-01-06 09:00
+    elapsed      10m
+    remaining    15m
+    planned end  2025-01-06 09:25
+    task         JIRA-123
+    tags         backend, review
+    cycle        pomodoro 2 of 4
+    today        1 of 8 pomodoros
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn status_shows_a_break() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_data_file("break 2025-01-06T09:25:00Z");
+
+    // WHEN
+    let mut cmd = fx.cmd(["status", "--now", "2025-01-06T09:28:00Z"]);
+    cmd.env("TZ", "UTC");
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    timer       default
+    state       on a break
+    started at  2025-01-06 09:25
+    elapsed     3m
+    cycle       pomodoro 1 of 4
+    today       0 pomodoro(s)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn status_shows_a_stopped_timer() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    let mut cmd = fx.cmd(["status", "--timer", "pairing"]);
+    cmd.env("TZ", "UTC");
+
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    timer  pairing
+    state  stopped
+    cycle  pomodoro 1 of 4
+    today  0 pomodoro(s)
+
+    ----- stderr -----
+    ");
+}
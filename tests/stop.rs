@@ -40,3 +40,93 @@ fn stopping_a_timer_works() {
     ----- stderr -----
     ");
 }
+
+#[test]
+fn idle_msg_is_shown_after_stopping_a_timer() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut start_cmd = fx.cmd(["start", "--elapsed-mins", "12"]);
+    assert_cmd_snapshot!(start_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+    let mut stop_cmd = fx.cmd(["stop"]);
+    assert_cmd_snapshot!(stop_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+
+    // WHEN
+    // THEN
+    let mut show_cmd = fx.cmd(["--idle-msg", "idle for {idle}"]);
+    assert_cmd_snapshot!(show_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     idle for 0m 
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn idle_msg_can_be_set_in_the_config_file() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file("idle_msg = \"idle for {idle}\"\n");
+
+    // WHEN
+    // THEN
+    let mut show_cmd = fx.base_cmd();
+    assert_cmd_snapshot!(show_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     idle for - 
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn idle_msg_flag_takes_precedence_over_the_config_file() {
+    // GIVEN
+    let fx = Fixture::new();
+    fx.write_config_file("idle_msg = \"idle for {idle}\"\n");
+
+    // WHEN
+    // THEN
+    let mut show_cmd = fx.cmd(["--idle-msg", "idle"]);
+    assert_cmd_snapshot!(show_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     idle 
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn idle_msg_is_shown_when_no_timer_has_been_started() {
+    // GIVEN
+    let fx = Fixture::new();
+
+    // WHEN
+    // THEN
+    let mut show_cmd = fx.cmd(["--idle-msg", "idle for {idle}"]);
+    assert_cmd_snapshot!(show_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     idle for - 
+
+    ----- stderr -----
+    ");
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn named_timers_keep_independent_state() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut start_cmd = fx.cmd(["--timer", "pairing", "start", "--elapsed-mins", "12"]);
+    assert_cmd_snapshot!(start_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+    let mut break_cmd = fx.cmd(["break"]);
+    assert_cmd_snapshot!(break_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+
+    // WHEN
+    // THEN
+    let mut show_named_cmd = fx.cmd(["--timer", "pairing"]);
+    assert_cmd_snapshot!(show_named_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     ▪▪▪▪▫▫▫▫▫▫ 
+
+    ----- stderr -----
+    ");
+
+    let mut show_default_cmd = fx.base_cmd();
+    assert_cmd_snapshot!(show_default_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     \o/ 
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn listing_timers_shows_active_timers() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut start_cmd = fx.cmd(["start", "--timer", "pairing", "--elapsed-mins", "12"]);
+    assert_cmd_snapshot!(start_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+    let mut break_cmd = fx.cmd(["break", "--timer", "personal"]);
+    assert_cmd_snapshot!(break_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+    let mut stop_cmd = fx.cmd(["stop", "--timer", "stopped"]);
+    assert_cmd_snapshot!(stop_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+
+    // WHEN
+    // THEN
+    let mut list_cmd = fx.cmd(["list"]);
+    assert_cmd_snapshot!(list_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    pairing   ▪▪▪▪▫▫▫▫▫▫
+    personal  \o/
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn showing_several_timers_together_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut start_cmd = fx.cmd(["start", "--timer", "pairing", "--elapsed-mins", "12"]);
+    assert_cmd_snapshot!(start_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+    let mut break_cmd = fx.cmd(["break", "--timer", "personal"]);
+    assert_cmd_snapshot!(break_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+    let mut stop_cmd = fx.cmd(["stop", "--timer", "stopped"]);
+    assert_cmd_snapshot!(stop_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+
+    // WHEN
+    // THEN
+    let mut show_cmd = fx.cmd([
+        "--timer",
+        "pairing",
+        "--timer",
+        "personal",
+        "--timer",
+        "stopped",
+        "--num-blocks",
+        "5",
+    ]);
+    assert_cmd_snapshot!(show_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     pairing ▪▪▫▫▫ | personal \o/ 
+
+    ----- stderr -----
+    ");
+}
+
+//------------//
+//  FAILURES  //
+//------------//
+
+#[test]
+fn fails_if_timer_name_is_invalid() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut start_cmd = fx.cmd(["start", "--timer", "../pairing"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(start_cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: timer name can only contain alphanumeric characters, '-', and '_'
+    ");
+}
+
+#[test]
+fn fails_if_several_timers_are_given_for_an_action() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut start_cmd = fx.cmd(["start", "--timer", "a", "--timer", "b"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(start_cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: only one timer can be specified for this command
+    ");
+}
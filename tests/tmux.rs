@@ -0,0 +1,98 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+#[cfg(unix)]
+fn write_fake_tmux(dir: &std::path::Path, session: &str) {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join("tmux");
+    fs::write(&path, format!("#!/bin/sh\necho '{session}'\n"))
+        .expect("fake tmux should've been written");
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+        .expect("fake tmux should've been made executable");
+}
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[cfg(unix)]
+#[test]
+fn tmux_scope_keeps_state_per_session() {
+    // GIVEN
+    let fx = Fixture::new();
+    let bin_dir = tempfile::tempdir().expect("temporary directory should've been created");
+    write_fake_tmux(bin_dir.path(), "work");
+    let path = format!(
+        "{}:{}",
+        bin_dir.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    let mut start_cmd = fx.cmd(["start", "--tmux-scope", "--elapsed-mins", "12"]);
+    start_cmd
+        .env("TMUX", "/tmp/tmux-1000/default,1,0")
+        .env("PATH", &path);
+    assert_cmd_snapshot!(start_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+
+    // WHEN
+    // THEN
+    let mut show_scoped_cmd = fx.cmd(["--tmux-scope"]);
+    show_scoped_cmd
+        .env("TMUX", "/tmp/tmux-1000/default,1,0")
+        .env("PATH", &path);
+    assert_cmd_snapshot!(show_scoped_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     ▪▪▪▪▫▫▫▫▫▫ 
+
+    ----- stderr -----
+    ");
+
+    let mut list_cmd = fx.cmd(["list"]);
+    assert_cmd_snapshot!(list_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    tmux-work  ▪▪▪▪▫▫▫▫▫▫
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn tmux_scope_falls_back_to_global_timer_outside_tmux() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut start_cmd = fx.cmd(["start", "--tmux-scope", "--elapsed-mins", "12"]);
+    start_cmd.env_remove("TMUX");
+    assert_cmd_snapshot!(start_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+
+    ----- stderr -----
+    ");
+
+    // WHEN
+    // THEN
+    let mut show_cmd = fx.base_cmd();
+    assert_cmd_snapshot!(show_cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+     ▪▪▪▪▫▫▫▫▫▫ 
+
+    ----- stderr -----
+    ");
+}